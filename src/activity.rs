@@ -0,0 +1,322 @@
+use crate::world::{Board, Boundary, Cell};
+
+/// Keeps track of which square tiles of the board are still changing, so a
+/// generation only has to work out the tiles that might change again. A tile
+/// can be skipped and copied forward when neither it nor any tile within
+/// reach of its cells changed since the last generation, and its own cells
+/// came out of the last generation as they went in. History bits like `0b10`
+/// are part of a cell's state, so a cell that just died keeps its tile
+/// active until the bit has been cleared.
+#[derive(Clone, Debug)]
+pub struct Activity {
+	enabled: bool,
+	tile_size: usize,
+	tiles_wide: usize,
+	tiles_high: usize,
+	/// Whether each tile is worked out this generation
+	active: Vec<bool>,
+	/// Whether each tile came out of the last generation differently from
+	/// how it went in
+	moving: Vec<bool>,
+	/// Whether each tile went into this generation differently from how it
+	/// went into the last one, painted cells and overlays included
+	changed: Vec<bool>,
+	/// The scratch board the last generation started from
+	seen: Vec<Cell>,
+	/// Whether the last generation grew territory, since that's worked out
+	/// differently
+	growth_enabled: bool,
+	stale: bool,
+}
+
+impl Activity {
+	pub const DEFAULT_TILE_SIZE: usize = 16;
+
+	pub fn new(tile_size: usize) -> Activity {
+		Activity {
+			enabled: true,
+			tile_size: tile_size.max(1),
+			tiles_wide: 0,
+			tiles_high: 0,
+			active: Vec::new(),
+			moving: Vec::new(),
+			changed: Vec::new(),
+			seen: Vec::new(),
+			growth_enabled: false,
+			stale: true,
+		}
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.enabled
+	}
+
+	/// Turns tracking on or off. Every tile is active while it's off.
+	pub fn set_enabled(&mut self, enabled: bool) {
+		self.enabled = enabled;
+		self.stale = true;
+	}
+
+	pub fn tile_size(&self) -> usize {
+		self.tile_size
+	}
+
+	pub fn tiles_wide(&self) -> usize {
+		self.tiles_wide
+	}
+
+	pub fn tiles_high(&self) -> usize {
+		self.tiles_high
+	}
+
+	/// Whether each tile was worked out in the last generation, row by row
+	pub fn tiles(&self) -> &[bool] {
+		&self.active
+	}
+
+	pub fn is_active(&self, row: usize, col: usize) -> bool {
+		self.active[(row / self.tile_size) * self.tiles_wide + col / self.tile_size]
+	}
+
+	/// Forgets what the board looked like, so that the next generation works
+	/// out every tile. Called whenever the rules themselves change.
+	pub fn invalidate(&mut self) {
+		self.stale = true;
+	}
+
+	/// Rebuilds the tiles for a board of a new size, with every tile active
+	/// until the next generation has been planned
+	pub fn resize(&mut self, width: usize, height: usize) {
+		self.tiles_wide = width.div_ceil(self.tile_size);
+		self.tiles_high = height.div_ceil(self.tile_size);
+		let tiles = self.tiles_wide * self.tiles_high;
+		for tiles_of in [&mut self.active, &mut self.moving, &mut self.changed] {
+			tiles_of.clear();
+			tiles_of.resize(tiles, true);
+		}
+		self.seen.clear();
+		self.stale = true;
+	}
+
+	/// Picks the tiles to work out this generation from the scratch board,
+	/// given how far the rules reach and which cells can't be skipped at all
+	pub(crate) fn plan(
+		&mut self,
+		scratch_board: &Board,
+		growth_enabled: bool,
+		reach: usize,
+		pinned: impl Fn(usize) -> bool,
+	) {
+		let (width, height) = (scratch_board.width, scratch_board.height);
+		let (tiles_wide, tiles_high) = (
+			width.div_ceil(self.tile_size),
+			height.div_ceil(self.tile_size),
+		);
+		if (tiles_wide, tiles_high) != (self.tiles_wide, self.tiles_high)
+			|| self.seen.len() != scratch_board.cells.len()
+			|| growth_enabled != self.growth_enabled
+		{
+			self.tiles_wide = tiles_wide;
+			self.tiles_high = tiles_high;
+			self.growth_enabled = growth_enabled;
+			self.active.resize(tiles_wide * tiles_high, true);
+			self.moving.resize(tiles_wide * tiles_high, true);
+			self.changed.resize(tiles_wide * tiles_high, true);
+			self.stale = true;
+		}
+
+		if self.stale || !self.enabled {
+			self.active.fill(true);
+		} else {
+			self.changed.fill(false);
+			for (idx, (cell, seen)) in scratch_board.iter().zip(&self.seen).enumerate() {
+				if cell != seen {
+					let tile = self.tile_of(width, idx);
+					self.changed[tile] = true;
+				}
+			}
+
+			self.active.copy_from_slice(&self.moving);
+			// A ragged last tile on a torus can leave the cells just across the
+			// seam a tile further away
+			let wraps = scratch_board.boundary == Boundary::Torus;
+			let ragged = width % self.tile_size != 0 || height % self.tile_size != 0;
+			let reach = (reach.div_ceil(self.tile_size) + (wraps && ragged) as usize) as isize;
+			for tile in 0..self.changed.len() {
+				if !self.changed[tile] {
+					continue;
+				}
+				let (tile_row, tile_col) = (tile / tiles_wide, tile % tiles_wide);
+				for dr in -reach..=reach {
+					for dc in -reach..=reach {
+						let near_row = neighbor_tile(tile_row, dr, tiles_high, wraps);
+						let near_col = neighbor_tile(tile_col, dc, tiles_wide, wraps);
+						if let (Some(near_row), Some(near_col)) = (near_row, near_col) {
+							self.active[near_row * tiles_wide + near_col] = true;
+						}
+					}
+				}
+			}
+
+			for idx in 0..scratch_board.cells.len() {
+				if pinned(idx) {
+					let tile = self.tile_of(width, idx);
+					self.active[tile] = true;
+				}
+			}
+		}
+
+		self.seen.clone_from(&scratch_board.cells);
+		self.stale = false;
+	}
+
+	/// Notes which of the tiles worked out this generation changed. Runs on
+	/// the next board as the rules left it, before anything else has a go
+	/// at it.
+	pub(crate) fn record(&mut self, next_board: &Board) {
+		self.moving.fill(false);
+		for (idx, (cell, seen)) in next_board.iter().zip(&self.seen).enumerate() {
+			let tile = self.tile_of(next_board.width, idx);
+			if self.active[tile] && cell != seen {
+				self.moving[tile] = true;
+			}
+		}
+	}
+
+	fn tile_of(&self, width: usize, idx: usize) -> usize {
+		let (row, col) = (idx / width, idx % width);
+		(row / self.tile_size) * self.tiles_wide + col / self.tile_size
+	}
+}
+
+impl Default for Activity {
+	fn default() -> Self {
+		Activity::new(Activity::DEFAULT_TILE_SIZE)
+	}
+}
+
+/// The tile `offset` tiles away along one axis, wrapping around on a torus
+fn neighbor_tile(tile: usize, offset: isize, tiles: usize, wraps: bool) -> Option<usize> {
+	let pos = tile as isize + offset;
+	if wraps {
+		Some(pos.rem_euclid(tiles as isize) as usize)
+	} else {
+		usize::try_from(pos).ok().filter(|&pos| pos < tiles)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::Activity;
+	use crate::rules::{Cyclic, Neighborhood, Ruleset};
+	use crate::world::{mix, Boundary, Fit, World};
+
+	#[test]
+	fn skipping_settled_tiles_changes_nothing() {
+		let world = |tracked| {
+			let mut world = World::new(72, 40);
+			world.set_boundary(Boundary::Torus);
+			world.activity_mut().set_enabled(tracked);
+			// Life around the edge, with a patch each of Brian's Brain, a
+			// Margolus rule and a Larger than Life rule in the middle
+			for idx in 0..72 * 40 {
+				let (row, col) = (idx / 72, idx % 72);
+				let ruleset = match (row, col) {
+					(8..=19, 8..=23) => Ruleset::BRIANS_BRAIN,
+					(8..=19, 40..=55) => Ruleset::CRITTERS,
+					(24..=35, 40..=63) => Ruleset::BOSCO,
+					_ => Ruleset::LIFE,
+				};
+				world.set_ruleset(idx, ruleset);
+				if mix(6, 0, row, col) % 3 == 1 {
+					world.board_mut()[idx].state = 0b01;
+				}
+			}
+			world
+		};
+
+		let mut tracked = world(true);
+		let mut untracked = world(false);
+		for generation in 0..80 {
+			if generation == 60 {
+				for world in [&mut tracked, &mut untracked] {
+					world.board_mut()[5 * 72 + 5].state = 0b01;
+					world.set_temporary_state(30 * 72 + 10, 0b01);
+				}
+			}
+			for world in [&mut tracked, &mut untracked] {
+				world.generate(generation % 20 < 10);
+				world.swap();
+			}
+			assert_eq!(tracked.board().cells, untracked.board().cells);
+		}
+	}
+
+	#[test]
+	fn still_lifes_go_quiet() {
+		let mut world = World::new(64, 64);
+		// A block, which never changes
+		for idx in [20 * 64 + 20, 20 * 64 + 21, 21 * 64 + 20, 21 * 64 + 21] {
+			world.board_mut()[idx].state = 0b01;
+		}
+		for _ in 0..3 {
+			world.generate(false);
+			world.swap();
+		}
+		assert!(world.activity().tiles().iter().all(|active| !active));
+
+		world.board_mut()[40 * 64 + 40].state = 0b01;
+		world.generate(false);
+		world.swap();
+		let active = world.activity().tiles().iter().filter(|active| **active);
+		assert_eq!(active.count(), 9);
+		assert!(world.activity().is_active(40, 40));
+	}
+
+	#[test]
+	fn long_range_waves_wake_tiles_across_the_edge() {
+		let world = |tracked| {
+			let mut world = World::new(64, 64);
+			*world.activity_mut() = Activity::new(4);
+			world.activity_mut().set_enabled(tracked);
+			let cyclic = world
+				.rules_mut()
+				.register(Box::new(Cyclic::new(3, 1, 10, Neighborhood::Moore)))
+				.unwrap();
+			for idx in 0..64 * 64 {
+				world.set_ruleset(idx, cyclic);
+				world.board_mut()[idx].state = 0;
+			}
+			world
+		};
+
+		let mut tracked = world(true);
+		let mut untracked = world(false);
+		for generation in 0..6 {
+			if generation == 3 {
+				// A single cell of the next color sets off a wave ten cells
+				// wide, reaching tiles three away
+				for world in [&mut tracked, &mut untracked] {
+					world.board_mut()[30 * 64 + 30].state = 1 << 1;
+				}
+			}
+			for world in [&mut tracked, &mut untracked] {
+				world.generate(false);
+				world.swap();
+			}
+			assert_eq!(tracked.board().cells, untracked.board().cells);
+		}
+		assert!(tracked.activity().is_active(30, 40));
+	}
+
+	#[test]
+	fn resizing_rebuilds_the_tiles() {
+		let mut world = World::new(64, 64);
+		world.generate(false);
+		world.swap();
+
+		world.resize(100, 90, Fit::Crop);
+		assert_eq!(world.activity().tiles().len(), 7 * 6);
+		assert!(world.activity().is_active(89, 99));
+	}
+}
@@ -0,0 +1,131 @@
+use crate::rules::{Life, PackedBoard, Rule, Visibility};
+use crate::world::{mix, Board, Boundary, Cell};
+use std::time::{Duration, Instant};
+
+/// Times Life on a full board, one cell at a time with
+/// `Life::next_cell_state` against a word of 64 cells at a time on a packed
+/// board, and checks that both come out the same.
+#[derive(Debug)]
+pub struct Bench {
+	pub generations: u64,
+	pub width: usize,
+	pub height: usize,
+}
+
+impl Default for Bench {
+	fn default() -> Self {
+		Bench {
+			generations: 100,
+			width: 1920,
+			height: 1080,
+		}
+	}
+}
+
+#[derive(Debug)]
+pub struct Timings {
+	pub per_cell: Duration,
+	pub packed: Duration,
+}
+
+impl Bench {
+	pub fn run(&self) -> Timings {
+		let board = Board {
+			cells: (0..self.width * self.height)
+				.map(|idx| Cell {
+					state: (mix(0, 0, idx / self.width, idx % self.width) % 2) as u8,
+					..Default::default()
+				})
+				.collect(),
+			width: self.width,
+			height: self.height,
+			generation: 0,
+			boundary: Boundary::Torus,
+		};
+
+		let mut per_cell_board = board.clone();
+		let mut next_board = board.clone();
+		let start = Instant::now();
+		for _ in 0..self.generations {
+			for row in 0..self.height {
+				for col in 0..self.width {
+					next_board[row * self.width + col].state =
+						Life.next_cell_state(&per_cell_board, row, col, Visibility::ALL);
+				}
+			}
+			std::mem::swap(&mut per_cell_board, &mut next_board);
+		}
+		let per_cell = start.elapsed();
+
+		let mut packed_board = board.clone();
+		let mut packed = PackedBoard::default();
+		let start = Instant::now();
+		for _ in 0..self.generations {
+			packed.pack(&packed_board);
+			for row in 0..self.height {
+				for word in 0..packed.words_per_row() {
+					let alive = packed.word(row, word);
+					let counts = packed.count_neighbors(row, word);
+					let next = Life::RULE.next_alive_packed(alive, &counts);
+					for bit in 0..64 {
+						let Some(col) = (word * 64 + bit)
+							.checked_sub(1)
+							.filter(|&col| col < self.width)
+						else {
+							continue;
+						};
+						next_board[row * self.width + col].state =
+							(next >> bit & 1) as u8 | ((alive >> bit & 1) as u8) << 1;
+					}
+				}
+			}
+			std::mem::swap(&mut packed_board, &mut next_board);
+		}
+		let packed_time = start.elapsed();
+
+		assert_eq!(
+			per_cell_board.cells, packed_board.cells,
+			"the packed kernel drifted from Life"
+		);
+		Timings {
+			per_cell,
+			packed: packed_time,
+		}
+	}
+}
+
+pub fn run_from_args(mut args: impl Iterator<Item = String>) {
+	let mut bench = Bench::default();
+	if let Some(generations) = args.next().and_then(|arg| arg.parse().ok()) {
+		bench.generations = generations;
+	}
+
+	let Timings { per_cell, packed } = bench.run();
+	let per_generation = |time: Duration| time / bench.generations.max(1) as u32;
+	println!(
+		"{}x{} board, {} generations",
+		bench.width, bench.height, bench.generations
+	);
+	println!("per cell: {:?} a generation", per_generation(per_cell));
+	println!(
+		"packed:   {:?} a generation, {:.1}x faster",
+		per_generation(packed),
+		per_cell.as_secs_f64() / packed.as_secs_f64()
+	);
+}
+
+#[cfg(test)]
+mod test {
+	use super::Bench;
+
+	#[test]
+	fn packed_and_per_cell_life_agree() {
+		// `run` checks the boards itself
+		Bench {
+			generations: 8,
+			width: 130,
+			height: 20,
+		}
+		.run();
+	}
+}
@@ -0,0 +1,65 @@
+use crate::world::World;
+use nannou::color::Blend;
+
+/// The color of a cell, laid out the way the shader reads it
+#[repr(C, align(16))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color(pub f32, pub f32, pub f32);
+
+/// Fills in the color of every cell of the world for a frame, reusing the
+/// space left over from the last one
+pub fn fill_cell_colors(
+	colors: &mut Vec<Color>,
+	world: &World,
+	blend_overlay: bool,
+	energy_heatmap: bool,
+	activity_overlay: bool,
+) {
+	let rules = world.rules();
+	colors.clear();
+	match world.energy().filter(|_| energy_heatmap) {
+		Some(energy) => colors.extend(
+			energy
+				.levels()
+				.iter()
+				.map(|level| heat_color(level / energy.capacity())),
+		),
+		None => colors.extend(
+			world
+				.board()
+				.cells
+				.iter()
+				.zip(world.temporary_rulesets())
+				.map(|(cell, maybe_ruleset)| {
+					let ruleset = maybe_ruleset.unwrap_or(cell.ruleset);
+					let color = if blend_overlay {
+						rules
+							.rule_color(ruleset)
+							.lighten(rules.color(ruleset, *cell))
+					} else {
+						rules.color(ruleset, *cell)
+					};
+					Color(color.red, color.green, color.blue)
+				}),
+		),
+	}
+	let activity = world.activity();
+	if activity_overlay && !activity.tiles().is_empty() {
+		let width = world.board().width;
+		for (idx, color) in colors.iter_mut().enumerate() {
+			if activity.is_active(idx / width, idx % width) {
+				*color = Color(color.0 * 0.6, color.1 * 0.6 + 0.1, color.2 * 0.6 + 0.4);
+			}
+		}
+	}
+}
+
+/// Black through red and yellow to white, as `t` goes from 0 to 1
+fn heat_color(t: f32) -> Color {
+	let t = t.clamp(0.0, 1.0) * 3.0;
+	Color(
+		t.min(1.0),
+		(t - 1.0).clamp(0.0, 1.0),
+		(t - 2.0).clamp(0.0, 1.0),
+	)
+}
@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+/// Settings read at startup from the command line, and optionally from a
+/// config file of `key = value` lines with `#` comments. Options given on the
+/// command line win over the file.
+///
+/// ```text
+/// memento-mori --width 320 --height 180 --cell-size 6 --rate 30
+/// memento-mori --config installation.conf
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+	pub board_width: usize,
+	pub board_height: usize,
+	/// Width and height of a cell on screen, in points
+	pub cell_size: usize,
+	/// How long to wait between generations
+	pub generation_rate: Duration,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Config {
+			board_width: 256,
+			board_height: 256,
+			cell_size: 4,
+			generation_rate: Duration::from_millis(1000 / 15),
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+	UnknownOption(String),
+	MissingValue(String),
+	InvalidValue(String, String),
+	/// A line of the config file that isn't `key = value`
+	InvalidLine(usize),
+	Unreadable(String, std::io::Error),
+}
+
+impl std::fmt::Display for ConfigError {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ConfigError::UnknownOption(option) => write!(fmt, "unknown option {option:?}"),
+			ConfigError::MissingValue(option) => write!(fmt, "missing value for {option}"),
+			ConfigError::InvalidValue(option, value) => {
+				write!(fmt, "invalid value {value:?} for {option}")
+			}
+			ConfigError::InvalidLine(line) => write!(fmt, "expected `key = value` on line {line}"),
+			ConfigError::Unreadable(path, e) => write!(fmt, "couldn't read {path}: {e}"),
+		}
+	}
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+	/// Reads `--width`, `--height`, `--cell-size`, `--rate` (generations a
+	/// second) and `--config <file>`
+	pub fn from_args(mut args: impl Iterator<Item = String>) -> Result<Config, ConfigError> {
+		let mut options = Vec::new();
+		let mut config_path = None;
+		while let Some(arg) = args.next() {
+			let key = arg
+				.strip_prefix("--")
+				.ok_or_else(|| ConfigError::UnknownOption(arg.clone()))?;
+			let value = args
+				.next()
+				.ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+			if key == "config" {
+				config_path = Some(value);
+			} else {
+				options.push((key.to_string(), value));
+			}
+		}
+
+		let mut config = Config::default();
+		if let Some(path) = config_path {
+			let text = std::fs::read_to_string(&path)
+				.map_err(|e| ConfigError::Unreadable(path.clone(), e))?;
+			config.read_file(&text)?;
+		}
+		for (key, value) in &options {
+			config.set(key, value)?;
+		}
+		Ok(config)
+	}
+
+	/// Applies the settings in the text of a config file
+	pub fn read_file(&mut self, text: &str) -> Result<(), ConfigError> {
+		for (line_idx, line) in text.lines().enumerate() {
+			let line = line.split('#').next().unwrap_or("").trim();
+			if line.is_empty() {
+				continue;
+			}
+			let (key, value) = line
+				.split_once('=')
+				.ok_or(ConfigError::InvalidLine(line_idx + 1))?;
+			self.set(&key.trim().replace('_', "-"), value.trim())?;
+		}
+		Ok(())
+	}
+
+	fn set(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+		let invalid = || ConfigError::InvalidValue(key.to_string(), value.to_string());
+		let positive = || {
+			value
+				.parse::<usize>()
+				.ok()
+				.filter(|&value| value > 0)
+				.ok_or_else(invalid)
+		};
+		match key {
+			"width" => self.board_width = positive()?,
+			"height" => self.board_height = positive()?,
+			"cell-size" => self.cell_size = positive()?,
+			"rate" => {
+				let rate = value
+					.parse::<f64>()
+					.ok()
+					.filter(|&rate| rate > 0.0 && rate.is_finite())
+					.ok_or_else(invalid)?;
+				self.generation_rate = Duration::from_secs_f64(rate.recip());
+			}
+			_ => return Err(ConfigError::UnknownOption(key.to_string())),
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{Config, ConfigError};
+	use std::time::Duration;
+
+	fn args(args: &str) -> impl Iterator<Item = String> + '_ {
+		args.split_whitespace().map(String::from)
+	}
+
+	#[test]
+	fn options_override_defaults_and_files() {
+		let config = Config::from_args(args("--width 320 --rate 30")).unwrap();
+		assert_eq!(config.board_width, 320);
+		assert_eq!(config.board_height, 256);
+		assert_eq!(config.generation_rate, Duration::from_secs_f64(1.0 / 30.0));
+
+		let mut config = Config::default();
+		config
+			.read_file("# An installation\nheight = 180\ncell_size = 6 # points\n")
+			.unwrap();
+		assert_eq!((config.board_height, config.cell_size), (180, 6));
+
+		assert!(matches!(
+			Config::from_args(args("--width 0")),
+			Err(ConfigError::InvalidValue(..))
+		));
+		assert!(matches!(
+			Config::from_args(args("--depth 3")),
+			Err(ConfigError::UnknownOption(_))
+		));
+		assert!(matches!(
+			Config::from_args(args("--height")),
+			Err(ConfigError::MissingValue(_))
+		));
+		assert!(matches!(
+			config.read_file("width 3"),
+			Err(ConfigError::InvalidLine(1))
+		));
+	}
+}
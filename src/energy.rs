@@ -0,0 +1,249 @@
+use crate::rules::{Registry, Ruleset};
+use crate::world::{Board, Fit};
+
+/// Where the environment's energy comes from
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Supply {
+	/// Every cell takes in the same amount each generation
+	Uniform(f32),
+	/// The amount ramps from `left` at the left edge of the board to `right`
+	/// at the right edge
+	Gradient { left: f32, right: f32 },
+	/// Nothing, until it's painted in
+	Painted,
+}
+
+/// A field of energy laid over the board. Every cell takes energy in from
+/// the supply each generation, up to its capacity, and live cells burn what
+/// their rule's metabolism asks for. A live cell that runs dry starves,
+/// whatever its rule would have done. Rules that use energy see how much
+/// their cell has left going into each generation.
+#[derive(Clone, Debug)]
+pub struct Energy {
+	width: usize,
+	levels: Vec<f32>,
+	supply: Vec<f32>,
+	source: Supply,
+	capacity: f32,
+}
+
+impl Energy {
+	pub const DEFAULT_CAPACITY: f32 = 10.0;
+
+	pub fn new(width: usize, height: usize, source: Supply) -> Energy {
+		let supply = (0..width * height)
+			.map(|idx| match source {
+				Supply::Uniform(amount) => amount,
+				Supply::Gradient { left, right } => {
+					let t = (idx % width) as f32 / (width - 1).max(1) as f32;
+					left + (right - left) * t
+				}
+				Supply::Painted => 0.0,
+			})
+			.collect();
+
+		Energy {
+			width,
+			levels: vec![Energy::DEFAULT_CAPACITY; width * height],
+			supply,
+			source,
+			capacity: Energy::DEFAULT_CAPACITY,
+		}
+	}
+
+	/// Sets how much energy a cell can hold, and fills every cell up to it
+	pub fn with_capacity(mut self, capacity: f32) -> Energy {
+		self.capacity = capacity;
+		self.refill();
+		self
+	}
+
+	pub fn source(&self) -> Supply {
+		self.source
+	}
+
+	pub fn capacity(&self) -> f32 {
+		self.capacity
+	}
+
+	pub fn levels(&self) -> &[f32] {
+		&self.levels
+	}
+
+	pub fn level(&self, row: usize, col: usize) -> f32 {
+		self.levels[row * self.width + col]
+	}
+
+	pub fn supply(&self, row: usize, col: usize) -> f32 {
+		self.supply[row * self.width + col]
+	}
+
+	pub fn paint_supply(&mut self, idx: usize, amount: f32) {
+		self.supply[idx] = amount;
+	}
+
+	/// Carries the field over to a board of a new size. Painted supply is
+	/// carried over along with the levels, and the supply is laid out again
+	/// for the other sources.
+	pub fn resize(&mut self, width: usize, height: usize, fit: Fit) {
+		let from = (self.width, self.levels.len() / self.width.max(1));
+		let to = (width, height);
+		self.levels = fit.resample(&self.levels, from, to, self.capacity);
+		self.supply = match self.source {
+			Supply::Painted => fit.resample(&self.supply, from, to, 0.0),
+			source => Energy::new(width, height, source).supply,
+		};
+		self.width = width;
+	}
+
+	pub fn refill(&mut self) {
+		self.levels.fill(self.capacity);
+	}
+
+	/// Runs one generation of metabolism over a freshly generated board.
+	/// Cells under a temporary ruleset are left alone, since they aren't
+	/// running their own rule.
+	pub fn metabolize(
+		&mut self,
+		rules: &Registry,
+		board: &mut Board,
+		temporary_rulesets: &[Option<Ruleset>],
+	) {
+		for (idx, level) in self.levels.iter_mut().enumerate() {
+			if temporary_rulesets[idx].is_some() {
+				continue;
+			}
+
+			let cell = board[idx];
+			let burn = rules.metabolism(cell);
+			*level = (*level + self.supply[idx]).min(self.capacity) - burn;
+			if *level <= 0.0 {
+				*level = 0.0;
+				if burn > 0.0 {
+					board[idx] = rules.starve(cell);
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{Energy, Supply};
+	use crate::rules::{Rule, Visibility};
+	use crate::world::{Board, World};
+	use nannou::color::LinSrgba;
+	use std::fmt::Write;
+
+	/// Lives for as long as its cell has at least half its energy left
+	#[derive(Debug)]
+	struct Hungry;
+
+	impl Rule for Hungry {
+		fn on(&self) -> u8 {
+			0b01
+		}
+
+		fn off(&self) -> u8 {
+			0b00
+		}
+
+		fn random(&self) -> u8 {
+			0b01
+		}
+
+		fn uses_energy(&self) -> bool {
+			true
+		}
+
+		fn color(&self, _state: u8) -> LinSrgba {
+			LinSrgba::new(0.0, 0.0, 0.0, 1.0)
+		}
+
+		fn rule_color(&self) -> LinSrgba {
+			LinSrgba::new(0.0, 0.0, 0.0, 0.125)
+		}
+
+		fn next_cell_state(&self, _: &Board, _: usize, _: usize, _: Visibility) -> u8 {
+			0b00
+		}
+
+		fn next_cell_state_with_energy(
+			&self,
+			_: &Board,
+			_: usize,
+			_: usize,
+			_: Visibility,
+			energy: Option<f32>,
+		) -> u8 {
+			energy.is_some_and(|energy| energy >= Energy::DEFAULT_CAPACITY / 2.0) as u8
+		}
+
+		fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
+			write!(output, "{:01b}", state)
+		}
+	}
+
+	#[test]
+	fn cells_starve_when_they_burn_more_than_they_take_in() {
+		let mut world = World::new(6, 6);
+		// A block, which Life alone would keep forever
+		for idx in [14, 15, 20, 21] {
+			world.board_mut()[idx].state = 0b01;
+		}
+		world.set_energy(Some(
+			Energy::new(6, 6, Supply::Uniform(0.5)).with_capacity(2.0),
+		));
+
+		for _ in 0..2 {
+			world.generate(false);
+			world.swap();
+		}
+		assert_eq!(world.board()[14].state & 0b01, 0b01);
+		let energy = world.energy().unwrap();
+		assert_eq!(energy.level(2, 2), 0.5);
+		assert_eq!(energy.level(0, 0), 2.0);
+
+		world.generate(false);
+		world.swap();
+		assert!(world.board().iter().all(|cell| cell.state & 0b01 == 0));
+	}
+
+	#[test]
+	fn rules_can_read_energy() {
+		let mut world = World::new(4, 4);
+		let hungry = world.rules_mut().register(Box::new(Hungry)).unwrap();
+		world.fill_ruleset(hungry);
+		let mut energy = Energy::new(4, 4, Supply::Painted);
+		energy.paint_supply(5, 1.0);
+		world.set_energy(Some(energy));
+
+		world.generate(false);
+		world.swap();
+		assert!(world.board().iter().all(|cell| cell.state == 0b01));
+
+		// Every cell burns a unit a generation, and only one gets it back
+		for _ in 0..8 {
+			world.generate(false);
+			world.swap();
+		}
+		for (idx, cell) in world.board().iter().enumerate() {
+			assert_eq!(cell.state, (idx == 5) as u8);
+		}
+	}
+
+	#[test]
+	fn gradients_ramp_across_the_board() {
+		let energy = Energy::new(
+			5,
+			2,
+			Supply::Gradient {
+				left: 0.0,
+				right: 1.0,
+			},
+		);
+		assert_eq!(energy.supply(0, 0), 0.0);
+		assert_eq!(energy.supply(1, 2), 0.5);
+		assert_eq!(energy.supply(1, 4), 1.0);
+	}
+}
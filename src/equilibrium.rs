@@ -0,0 +1,215 @@
+use crate::rules::Ruleset;
+use crate::world::Board;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+/// The part of the board to watch for equilibrium
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Scope {
+	Board,
+	/// Every cell of one ruleset
+	Territory(Ruleset),
+	/// The connected patch of cells that share a ruleset with the cell at
+	/// this index, found again each generation
+	Region(usize),
+}
+
+/// A watched part of the board that has stopped changing, or only repeats
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Equilibrium {
+	/// 1 for a still life, or the period of an oscillator
+	pub period: usize,
+	/// The first generation of the repeating cycle
+	pub since: u64,
+}
+
+impl Equilibrium {
+	pub fn is_still(&self) -> bool {
+		self.period == 1
+	}
+}
+
+/// Watches part of the board by hashing it every generation, and notices
+/// when the hashes start repeating. A cycle has to play out twice in a row
+/// before it counts, so the longest period it can find is half the window.
+#[derive(Clone, Debug)]
+pub struct EquilibriumDetector {
+	scope: Scope,
+	window: usize,
+	/// Each generation and the hash of the scope in it, oldest first
+	hashes: VecDeque<(u64, u64)>,
+	equilibrium: Option<Equilibrium>,
+	just_settled: bool,
+	/// Scratch space for finding regions
+	region: Vec<bool>,
+	frontier: Vec<usize>,
+}
+
+impl EquilibriumDetector {
+	pub fn new(scope: Scope, window: usize) -> EquilibriumDetector {
+		EquilibriumDetector {
+			scope,
+			window: window.max(2),
+			hashes: VecDeque::with_capacity(window.max(2)),
+			equilibrium: None,
+			just_settled: false,
+			region: Vec::new(),
+			frontier: Vec::new(),
+		}
+	}
+
+	pub fn scope(&self) -> Scope {
+		self.scope
+	}
+
+	pub fn window(&self) -> usize {
+		self.window
+	}
+
+	pub fn equilibrium(&self) -> Option<Equilibrium> {
+		self.equilibrium
+	}
+
+	/// Whether the last generation observed is the one that settled into
+	/// the current equilibrium
+	pub fn just_settled(&self) -> bool {
+		self.just_settled
+	}
+
+	pub fn reset(&mut self) {
+		self.hashes.clear();
+		self.equilibrium = None;
+		self.just_settled = false;
+	}
+
+	pub fn observe(&mut self, board: &Board) -> Option<Equilibrium> {
+		let hash = self.hash(board);
+		if self.hashes.len() == self.window {
+			self.hashes.pop_front();
+		}
+		self.hashes.push_back((board.generation, hash));
+
+		let previous = self.equilibrium;
+		self.equilibrium = self.find_period().map(|period| match previous {
+			Some(equilibrium) if equilibrium.period == period => equilibrium,
+			_ => Equilibrium {
+				period,
+				since: self.settled_since(period),
+			},
+		});
+		self.just_settled = self.equilibrium.is_some() && self.equilibrium != previous;
+		self.equilibrium
+	}
+
+	/// The shortest period whose last cycle repeats the one before it
+	fn find_period(&self) -> Option<usize> {
+		let len = self.hashes.len();
+		(1..=len / 2).find(|&period| {
+			(len - period..len).all(|idx| self.hashes[idx].1 == self.hashes[idx - period].1)
+		})
+	}
+
+	/// Walks back through the window to the first generation that the cycle
+	/// repeats
+	fn settled_since(&self, period: usize) -> u64 {
+		let mut idx = self.hashes.len() - 1;
+		while idx >= period && self.hashes[idx].1 == self.hashes[idx - period].1 {
+			idx -= 1;
+		}
+		self.hashes[idx + 1 - period].0
+	}
+
+	fn hash(&mut self, board: &Board) -> u64 {
+		let mut hasher = DefaultHasher::new();
+		match self.scope {
+			Scope::Board => board.cells.hash(&mut hasher),
+			Scope::Territory(ruleset) => {
+				for (idx, cell) in board.iter().enumerate() {
+					if cell.ruleset == ruleset {
+						(idx, cell.state).hash(&mut hasher);
+					}
+				}
+			}
+			Scope::Region(seed) => {
+				self.find_region(board, seed);
+				for (idx, cell) in board.iter().enumerate() {
+					if self.region[idx] {
+						(idx, cell.state).hash(&mut hasher);
+					}
+				}
+			}
+		}
+		hasher.finish()
+	}
+
+	/// Floods out from `seed` to its von Neumann neighbors of the same
+	/// ruleset, following the board's boundary
+	fn find_region(&mut self, board: &Board, seed: usize) {
+		self.region.clear();
+		self.region.resize(board.cells.len(), false);
+		let ruleset = board[seed].ruleset;
+		self.region[seed] = true;
+		self.frontier.clear();
+		self.frontier.push(seed);
+
+		while let Some(idx) = self.frontier.pop() {
+			let (row, col) = (idx / board.width, idx % board.width);
+			for (dr, dc) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+				if let Some(neighbor) = board.neighbor_idx(row, col, dr, dc) {
+					if !self.region[neighbor] && board[neighbor].ruleset == ruleset {
+						self.region[neighbor] = true;
+						self.frontier.push(neighbor);
+					}
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::Scope;
+	use crate::rules::Ruleset;
+	use crate::world::World;
+
+	#[test]
+	fn oscillators_and_still_lifes_are_found() {
+		let mut world = World::new(8, 8);
+		// A blinker on the left, in Life's territory, and nothing at all in
+		// Seeds' territory on the right
+		for idx in [9, 17, 25] {
+			world.board_mut()[idx].state = 0b01;
+		}
+		for row in 0..8 {
+			for col in 5..8 {
+				world.set_ruleset(row * 8 + col, Ruleset::SEEDS);
+			}
+		}
+		world.watch_equilibrium(Scope::Board, 8);
+		world.watch_equilibrium(Scope::Territory(Ruleset::SEEDS), 8);
+		world.watch_equilibrium(Scope::Region(0), 8);
+
+		for _ in 0..8 {
+			world.generate(false);
+			world.swap();
+		}
+
+		let equilibria = world
+			.equilibrium_detectors()
+			.iter()
+			.map(|detector| detector.equilibrium().unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(equilibria[0].period, 2);
+		assert!(equilibria[0].since <= 2);
+		assert!(equilibria[1].is_still());
+		assert_eq!(equilibria[2].period, 2);
+		assert!(!world.equilibrium_detectors()[0].just_settled());
+
+		// Breaking the cycle unsettles it
+		world.board_mut()[63].state = 0b01;
+		world.generate(false);
+		world.swap();
+		assert_eq!(world.equilibrium_detectors()[0].equilibrium(), None);
+	}
+}
@@ -0,0 +1,324 @@
+use crate::equilibrium::Scope;
+use crate::rules::{Generations, LifeLike, Rule};
+use crate::world::{Boundary, World};
+use nannou::rand;
+
+/// Searches random Life-like and Generations rules for ones that keep
+/// churning on a random board without dying out, freezing or boiling into
+/// noise, and ranks them.
+#[derive(Debug)]
+pub struct Explorer {
+	pub samples: usize,
+	/// How many generations to run each rule for. At least one always runs.
+	pub generations: u64,
+	pub width: usize,
+	pub height: usize,
+}
+
+impl Default for Explorer {
+	fn default() -> Self {
+		Explorer {
+			samples: 200,
+			generations: 300,
+			width: 96,
+			height: 96,
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Score {
+	/// Variance of the live fraction of the board across generations
+	pub population_variance: f32,
+	/// Fraction of cells that change state each generation
+	pub activity: f32,
+	/// Shannon entropy of the 2x2 blocks of live cells on the final board,
+	/// from 0 for a uniform board to 1 when every block is equally likely
+	pub entropy: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rejection {
+	/// Every cell died by this generation
+	DiedOut(u64),
+	/// The board stopped changing, settled into an oscillator with a short
+	/// period, or barely changed at all
+	Froze,
+	/// Too many cells changed every generation to see any structure
+	Boiled,
+}
+
+#[derive(Debug)]
+pub struct Candidate {
+	pub rule: String,
+	pub score: Score,
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+	/// Surviving rules, best first
+	pub candidates: Vec<Candidate>,
+	pub died_out: usize,
+	pub froze: usize,
+	pub boiled: usize,
+}
+
+const IDEAL_ACTIVITY: f32 = 0.08;
+const MIN_ACTIVITY: f32 = 0.002;
+const MAX_ACTIVITY: f32 = 0.35;
+const IDEAL_POPULATION_DEVIATION: f32 = 0.005;
+/// Generations to look back over for the whole board repeating itself, which
+/// catches oscillators with periods up to half of it
+const FROZEN_WINDOW: usize = 8;
+
+impl Score {
+	/// Rules score highest when the board is mixed and structured, a moderate
+	/// number of cells change each generation, and the population keeps
+	/// moving. Activity falls off by how many times too high or too low it is.
+	pub fn total(&self) -> f32 {
+		let activity = (-(self.activity / IDEAL_ACTIVITY).ln().powi(2) / 2.0).exp();
+		let deviation = (self.population_variance.sqrt() / IDEAL_POPULATION_DEVIATION).min(1.0);
+		self.entropy * activity * (0.5 + 0.5 * deviation)
+	}
+}
+
+impl Explorer {
+	pub fn run(&self) -> Report {
+		let mut report = Report::default();
+
+		for _ in 0..self.samples {
+			let rule = random_rule();
+			let name = format!("{:?}", rule);
+			match self.evaluate(rule) {
+				Ok(score) => report.candidates.push(Candidate { rule: name, score }),
+				Err(Rejection::DiedOut(_)) => report.died_out += 1,
+				Err(Rejection::Froze) => report.froze += 1,
+				Err(Rejection::Boiled) => report.boiled += 1,
+			}
+		}
+
+		report
+			.candidates
+			.sort_by(|a, b| b.score.total().total_cmp(&a.score.total()));
+		report
+	}
+
+	/// Runs a rule on a random toroidal board and scores it. Only the second
+	/// half of the run is measured, after the random start has settled down.
+	pub fn evaluate(&self, rule: Box<dyn Rule>) -> Result<Score, Rejection> {
+		let mut world = World::new(self.width, self.height);
+		world.set_boundary(Boundary::Torus);
+		let ruleset = world
+			.rules_mut()
+			.register(rule)
+			.expect("registry has room for one more rule");
+		world.fill_ruleset(ruleset);
+		world.randomize();
+		self.measure(&mut world)
+	}
+
+	/// Runs the world on from the board it has, and scores it
+	fn measure(&self, world: &mut World) -> Result<Score, Rejection> {
+		world.watch_equilibrium(Scope::Board, FROZEN_WINDOW);
+
+		let cell_count = (self.width * self.height) as f32;
+		let generations = self.generations.max(1);
+		let measure_from = generations / 2;
+		let mut previous_states = world
+			.board()
+			.iter()
+			.map(|cell| cell.state)
+			.collect::<Vec<_>>();
+		let mut populations = Vec::with_capacity(generations as usize);
+		let mut changes = 0;
+
+		for generation in 1..=generations {
+			world.generate(false);
+			world.swap();
+
+			let mut live = 0;
+			let mut changed = 0;
+			for (cell, previous) in world.board().iter().zip(previous_states.iter_mut()) {
+				live += (cell.state & 0b01) as usize;
+				changed += (cell.state != *previous) as usize;
+				*previous = cell.state;
+			}
+
+			if live == 0 {
+				return Err(Rejection::DiedOut(generation));
+			}
+			let repeating = world.equilibrium_detectors()[0].equilibrium().is_some();
+			if changed == 0 || repeating {
+				return Err(Rejection::Froze);
+			}
+			if generation > measure_from {
+				populations.push(live as f32 / cell_count);
+				changes += changed;
+			}
+		}
+
+		let measured = populations.len() as f32;
+		let activity = changes as f32 / cell_count / measured;
+		if activity < MIN_ACTIVITY {
+			return Err(Rejection::Froze);
+		}
+		if activity > MAX_ACTIVITY {
+			return Err(Rejection::Boiled);
+		}
+
+		let mean = populations.iter().sum::<f32>() / measured;
+		let population_variance = populations
+			.iter()
+			.map(|population| (population - mean).powi(2))
+			.sum::<f32>()
+			/ measured;
+
+		Ok(Score {
+			population_variance,
+			activity,
+			entropy: block_entropy(world),
+		})
+	}
+}
+
+/// Picks a Life-like rule, or half the time a Generations rule with 3 to 8
+/// states. Rules with B0 are left out, since they flash the whole board on
+/// and off.
+fn random_rule() -> Box<dyn Rule> {
+	let birth = (1..=8).filter(|_| rand::random()).collect::<Vec<u8>>();
+	let survival = (0..=8).filter(|_| rand::random()).collect::<Vec<u8>>();
+	if rand::random() {
+		Box::new(LifeLike::new(&birth, &survival))
+	} else {
+		let states = rand::random_range(3, 9);
+		Box::new(Generations::new(&birth, &survival, states))
+	}
+}
+
+fn block_entropy(world: &World) -> f32 {
+	let board = world.board();
+	let mut counts = [0u32; 16];
+	for row in (0..board.height - 1).step_by(2) {
+		for col in (0..board.width - 1).step_by(2) {
+			let idx = row * board.width + col;
+			let block = board[idx].state & 0b01
+				| (board[idx + 1].state & 0b01) << 1
+				| (board[idx + board.width].state & 0b01) << 2
+				| (board[idx + board.width + 1].state & 0b01) << 3;
+			counts[block as usize] += 1;
+		}
+	}
+
+	let total = counts.iter().sum::<u32>() as f32;
+	let bits = counts
+		.iter()
+		.filter(|&&count| count > 0)
+		.map(|&count| {
+			let p = count as f32 / total;
+			-p * p.log2()
+		})
+		.sum::<f32>();
+	bits / 4.0
+}
+
+/// Runs the explorer from the command line, with the number of samples and
+/// generations to run each for as optional arguments, and prints the ranked
+/// rules.
+pub fn run_from_args(mut args: impl Iterator<Item = String>) {
+	let mut explorer = Explorer::default();
+	if let Some(samples) = args.next().and_then(|arg| arg.parse().ok()) {
+		explorer.samples = samples;
+	}
+	if let Some(generations) = args.next().and_then(|arg| arg.parse().ok()) {
+		explorer.generations = generations;
+	}
+
+	let report = explorer.run();
+	for (rank, candidate) in report.candidates.iter().enumerate() {
+		let Score {
+			population_variance,
+			activity,
+			entropy,
+		} = candidate.score;
+		println!(
+			"{:>3}. {:<24} score {:.3}  activity {:.3}  entropy {:.3}  variance {:.6}",
+			rank + 1,
+			candidate.rule,
+			candidate.score.total(),
+			activity,
+			entropy,
+			population_variance,
+		);
+	}
+	println!(
+		"{} of {} rules survived; {} died out, {} froze, {} boiled",
+		report.candidates.len(),
+		explorer.samples,
+		report.died_out,
+		report.froze,
+		report.boiled,
+	);
+}
+
+#[cfg(test)]
+mod test {
+	use super::{block_entropy, Explorer, Rejection};
+	use crate::rules::{LifeLike, Ruleset};
+	use crate::world::{Boundary, World};
+
+	#[test]
+	fn rules_that_die_out_are_rejected() {
+		let explorer = Explorer {
+			samples: 1,
+			generations: 10,
+			width: 16,
+			height: 16,
+		};
+		assert_eq!(
+			explorer.evaluate(Box::new(LifeLike::new(&[], &[]))),
+			Err(Rejection::DiedOut(1))
+		);
+
+		let explorer = Explorer {
+			generations: 0,
+			..explorer
+		};
+		assert_eq!(
+			explorer.evaluate(Box::new(LifeLike::new(&[], &[]))),
+			Err(Rejection::DiedOut(1))
+		);
+	}
+
+	#[test]
+	fn blinkers_are_frozen() {
+		let explorer = Explorer {
+			samples: 1,
+			generations: 40,
+			width: 16,
+			height: 16,
+		};
+		let mut world = World::new(16, 16);
+		world.set_boundary(Boundary::Torus);
+		world.fill_ruleset(Ruleset::LIFE);
+		for idx in [4 * 16 + 3, 4 * 16 + 4, 4 * 16 + 5] {
+			world.board_mut()[idx].state = 0b01;
+		}
+		assert_eq!(explorer.measure(&mut world), Err(Rejection::Froze));
+	}
+
+	#[test]
+	fn block_entropy_is_zero_for_uniform_boards() {
+		let mut world = World::new(8, 8);
+		assert_eq!(block_entropy(&world), 0.0);
+
+		// Alternating live rows make every block the same, too
+		for (idx, cell) in world.board_mut().iter_mut().enumerate() {
+			cell.ruleset = Ruleset::LIFE;
+			cell.state = ((idx / 8) % 2) as u8;
+		}
+		assert_eq!(block_entropy(&world), 0.0);
+
+		world.board_mut()[0].state = 0b01;
+		assert!(block_entropy(&world) > 0.0);
+	}
+}
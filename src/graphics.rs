@@ -1,7 +1,8 @@
+use crate::colors::{fill_cell_colors, Color};
 use crate::world::World;
-use nannou::color::Blend;
 use nannou::prelude::*;
 use nannou::wgpu;
+use std::cell::RefCell;
 
 struct BufferData {
 	vertices: [Vertex; 6],
@@ -13,10 +14,6 @@ struct BufferData {
 #[derive(Clone, Copy)]
 struct Vertex(f32, f32);
 
-#[repr(C, align(16))]
-#[derive(Clone, Copy)]
-struct Color(f32, f32, f32);
-
 #[repr(C)]
 #[derive(Copy, Clone)]
 struct Uniforms {
@@ -59,6 +56,8 @@ pub struct Graphics {
 	pub vertex_count: u32,
 	pub instance_count: u32,
 	pub color_buffer: wgpu::Buffer,
+	/// The colors of the cells, kept to fill in again every frame
+	colors: RefCell<Vec<Color>>,
 	pub bind_group: wgpu::BindGroup,
 	pub render_pipeline: wgpu::RenderPipeline,
 }
@@ -124,6 +123,7 @@ pub fn make_graphics(app: &App, width: usize, height: usize) -> Graphics {
 		vertex_count: buffer_data.vertices.len() as u32,
 		instance_count: buffer_data.instances.len() as u32,
 		color_buffer,
+		colors: RefCell::new(buffer_data.colors),
 		bind_group,
 		render_pipeline,
 		// texture,
@@ -131,23 +131,23 @@ pub fn make_graphics(app: &App, width: usize, height: usize) -> Graphics {
 	}
 }
 
-pub fn render_graphics(frame: &Frame, graphics: &Graphics, world: &World, blend_overlay: bool) {
-	let cell_colors: Vec<_> = world
-		.board()
-		.cells
-		.iter()
-		.zip(&world.temporary_rulesets)
-		.map(|(cell, maybe_ruleset)| {
-			let ruleset = maybe_ruleset.unwrap_or(cell.ruleset);
-			let color = if blend_overlay {
-				ruleset.rule_color().lighten(ruleset.color(*cell))
-			} else {
-				ruleset.color(*cell)
-			};
-			Color(color.red, color.green, color.blue)
-		})
-		.collect();
-	let colors_bytes = unsafe { wgpu::bytes::from_slice(&cell_colors) };
+pub fn render_graphics(
+	frame: &Frame,
+	graphics: &Graphics,
+	world: &World,
+	blend_overlay: bool,
+	energy_heatmap: bool,
+	activity_overlay: bool,
+) {
+	let mut cell_colors = graphics.colors.borrow_mut();
+	fill_cell_colors(
+		&mut cell_colors,
+		world,
+		blend_overlay,
+		energy_heatmap,
+		activity_overlay,
+	);
+	let colors_bytes = unsafe { wgpu::bytes::from_slice(&cell_colors[..]) };
 
 	frame
 		.device_queue_pair()
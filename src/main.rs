@@ -1,23 +1,60 @@
+mod activity;
+mod bench;
+mod colors;
+mod config;
+mod energy;
+mod equilibrium;
+mod explorer;
 mod graphics;
 mod model;
+mod noise;
 mod rules;
+mod stroke;
+mod workers;
 mod world;
 
+use crate::config::Config;
+use crate::energy::{Energy, Supply};
+use crate::equilibrium::Scope;
 use crate::graphics::{make_graphics, render_graphics};
 #[cfg(feature = "nite")]
 use crate::model::OniManager;
 use crate::model::{AnimationState, Brush, ColRow, DrawUserState, Model};
-use crate::rules::Ruleset;
-use crate::world::World;
+use crate::noise::{Effect, Noise, Pattern};
+use crate::rules::{Interactions, Ruleset};
+use crate::stroke::Stroke;
+use crate::world::{Fit, GrowthStrategy, TerritoryClaims, TerritoryDecay, UpdateMode, World};
 use nannou::prelude::*;
-use std::time::{Duration, Instant};
+use std::sync::OnceLock;
+use std::time::Instant;
 
-const BOARD_WIDTH: usize = 256;
-const BOARD_HEIGHT: usize = 256;
-const CELL_SIZE: usize = 4;
-const GENERATION_RATE: Duration = Duration::from_millis(1000 / 15);
+/// Read in `main`, before nannou calls `model` without any way to pass it in
+static CONFIG: OnceLock<Config> = OnceLock::new();
+const MAX_CELL_SIZE: usize = 32;
+const TERRITORY_DECAY_GENERATIONS: u16 = 60;
+const PAINTED_ENERGY_SUPPLY: f32 = 1.5;
+const EQUILIBRIUM_WINDOW: usize = 64;
+const NOISE_RATE: f32 = 1.0 / 1024.0;
 
 fn main() {
+	// `memento-mori explore [samples] [generations]` searches for new rules,
+	// and `memento-mori bench [generations]` times the packed Life kernel,
+	// without opening a window
+	let mut args = std::env::args().skip(1).peekable();
+	match args.peek().map(String::as_str) {
+		Some("explore") => return explorer::run_from_args(args.skip(1)),
+		Some("bench") => return bench::run_from_args(args.skip(1)),
+		_ => {}
+	}
+
+	match Config::from_args(args) {
+		Ok(config) => CONFIG.set(config).unwrap(),
+		Err(e) => {
+			eprintln!("memento-mori: {e}");
+			std::process::exit(2);
+		}
+	}
+
 	nannou::app(model)
 		.loop_mode(LoopMode::RefreshSync)
 		.event(event)
@@ -27,10 +64,12 @@ fn main() {
 }
 
 fn model(app: &App) -> Model {
+	let config = CONFIG.get().cloned().unwrap_or_default();
+	let (width, height) = (config.board_width, config.board_height);
 	app.new_window()
 		.size(
-			BOARD_WIDTH as u32 * CELL_SIZE as u32,
-			BOARD_HEIGHT as u32 * CELL_SIZE as u32,
+			(width * config.cell_size) as u32,
+			(height * config.cell_size) as u32,
 		)
 		.msaa_samples(1)
 		.build()
@@ -38,10 +77,10 @@ fn model(app: &App) -> Model {
 
 	app.set_exit_on_escape(false);
 
-	let graphics = make_graphics(app, BOARD_WIDTH, BOARD_HEIGHT);
+	let graphics = make_graphics(app, width, height);
 
 	#[cfg(feature = "nite")]
-	let oni_manager = match OniManager::create(BOARD_WIDTH, BOARD_HEIGHT) {
+	let oni_manager = match OniManager::create(width, height) {
 		Ok(oni) => Some(oni),
 		Err(e) => {
 			println!("OniManager init failed: {e:?}");
@@ -49,23 +88,32 @@ fn model(app: &App) -> Model {
 		}
 	};
 
+	let mut world = World::new(width, height);
+	world.watch_equilibrium(Scope::Board, EQUILIBRIUM_WINDOW);
+	let brush_ruleset = world.rules().next(Ruleset::default());
+
 	Model {
-		world: World::new(BOARD_WIDTH, BOARD_HEIGHT),
+		world,
 		brush: Brush {
 			size: 3,
-			ruleset: Ruleset::default().next(),
+			ruleset: brush_ruleset,
+			stroke: Stroke::new(width * height),
 			..Default::default()
 		},
 		draw_brush: false,
 		growth: false,
+		energy_heatmap: false,
+		activity_overlay: false,
 		graphics,
 		animation_state: AnimationState::Running,
-		last_generation_at: Instant::now() - GENERATION_RATE,
+		last_generation_at: Instant::now() - config.generation_rate,
+		config,
 		#[cfg(feature = "nite")]
 		oni_manager,
 		draw_user_state: DrawUserState::Draw,
 		record_frames: 0,
 		capture_frame: false,
+		window_shown: false,
 	}
 }
 
@@ -77,11 +125,21 @@ fn event(app: &App, model: &mut Model, event: Event) {
 		} => match window_event {
 			WindowEvent::Focused => app.set_loop_mode(LoopMode::RefreshSync),
 			WindowEvent::Unfocused => app.set_loop_mode(LoopMode::loop_ntimes(0)),
+			WindowEvent::Resized(size) if !model.window_shown => {
+				fit_board_to_window(app, model, size)
+			}
+			WindowEvent::Resized(size) => {
+				// Dragging the window bigger or smaller crops the board to fit
+				let cell_size = model.config.cell_size as f32;
+				let width = (size.x / cell_size).floor().max(1.0) as usize;
+				let height = (size.y / cell_size).floor().max(1.0) as usize;
+				resize_board(app, model, width, height, Fit::Crop);
+			}
 			WindowEvent::MouseEntered => model.draw_brush = true,
 			WindowEvent::MouseExited => model.draw_brush = false,
 			WindowEvent::MouseMoved(pos) => {
 				model.brush.pos = pos;
-				model.brush.col_row = get_cell_pos_under_pointer(pos);
+				model.brush.col_row = get_cell_pos_under_pointer(model, pos);
 			}
 			// WindowEvent::MousePressed(MouseButton::Left) => println("Mouse pressed: Left"),
 			// WindowEvent::MouseReleased(MouseButton::Left) => println("Mouse released: Left"),
@@ -90,23 +148,115 @@ fn event(app: &App, model: &mut Model, event: Event) {
 			// WindowEvent::MousePressed(MouseButton::Middle) => println!("Mouse pressed: Middle"),
 			// WindowEvent::MouseReleased(MouseButton::Middle) => println!("Mouse released: Middle"),
 			WindowEvent::KeyPressed(Key::Escape) => model.world.reset(),
+			WindowEvent::KeyPressed(Key::A) => {
+				// Shift changes the mode of the ruleset being painted with,
+				// instead of every ruleset's
+				let ruleset = model.brush.ruleset;
+				if app.keys.mods.shift() {
+					let mode = next_update_mode(model.world.ruleset_update_mode(ruleset));
+					model.world.set_ruleset_update_mode(ruleset, Some(mode))
+				} else {
+					let mode = next_update_mode(model.world.update_mode());
+					model.world.set_update_mode(mode)
+				}
+			}
+			WindowEvent::KeyPressed(Key::B) => {
+				let boundary = model.world.boundary().next();
+				model.world.set_boundary(boundary)
+			}
 			WindowEvent::KeyPressed(Key::C) => model.world.clear(),
+			WindowEvent::KeyPressed(Key::D) => {
+				// Toggle decay of abandoned territory into the ruleset being
+				// painted with
+				let decay = match model.world.territory_decay() {
+					Some(_) => None,
+					None => Some(TerritoryDecay {
+						background: model.brush.ruleset,
+						generations: TERRITORY_DECAY_GENERATIONS,
+					}),
+				};
+				model.world.set_territory_decay(decay)
+			}
+			WindowEvent::KeyPressed(Key::E) => {
+				let energy =
+					next_energy_supply(model.world.energy().map(Energy::source)).map(|supply| {
+						let board = model.world.board();
+						Energy::new(board.width, board.height, supply)
+					});
+				model.world.set_energy(energy)
+			}
+			WindowEvent::KeyPressed(Key::F) => {
+				if let Some(noise) = model.world.noise_mut() {
+					noise.effect = match noise.effect {
+						Effect::Flip => Effect::Kill,
+						Effect::Kill => Effect::Birth,
+						Effect::Birth => Effect::Flip,
+					}
+				}
+			}
 			WindowEvent::KeyPressed(Key::G) => model.growth = !model.growth,
+			WindowEvent::KeyPressed(Key::H) => model.energy_heatmap = !model.energy_heatmap,
+			WindowEvent::KeyPressed(Key::O) => model.activity_overlay = !model.activity_overlay,
+			WindowEvent::KeyPressed(Key::I) => {
+				// Toggle between every ruleset seeing every other, and each
+				// only seeing its own cells
+				let rules = model.world.rules_mut();
+				let interactions = if rules.interactions().is_uniform() {
+					Interactions::isolated(rules.len())
+				} else {
+					Interactions::default()
+				};
+				rules.set_interactions(interactions)
+			}
 			WindowEvent::KeyPressed(Key::R) => model.world.randomize(),
+			WindowEvent::KeyPressed(Key::S) => {
+				let strategy = next_growth_strategy(model.world.growth_strategy());
+				model.world.set_growth_strategy(strategy)
+			}
+			WindowEvent::KeyPressed(Key::T) => {
+				let claims = match model.world.territory_claims() {
+					TerritoryClaims::Live => TerritoryClaims::Present,
+					TerritoryClaims::Present => TerritoryClaims::Live,
+				};
+				model.world.set_territory_claims(claims)
+			}
+			WindowEvent::KeyPressed(Key::X) => {
+				let noise = next_noise(model.world.noise(), &model.brush);
+				model.world.set_noise(noise)
+			}
+			WindowEvent::KeyPressed(Key::Equals) => {
+				if let Some(noise) = model.world.noise_mut() {
+					noise.rate = (noise.rate * 2.0).min(1.0)
+				}
+			}
+			WindowEvent::KeyPressed(Key::Minus) => {
+				if let Some(noise) = model.world.noise_mut() {
+					noise.rate *= 0.5
+				}
+			}
 			WindowEvent::KeyPressed(Key::U) => {
 				model.draw_user_state = model.draw_user_state.toggle()
 			}
-			WindowEvent::KeyPressed(Key::Tab) => model.brush.ruleset = model.brush.ruleset.next(),
+			WindowEvent::KeyPressed(Key::Tab) => {
+				model.brush.ruleset = model.world.rules().next(model.brush.ruleset)
+			}
 			WindowEvent::KeyPressed(Key::Space) => {
 				model.animation_state = model.animation_state.frame_step()
 			}
+			WindowEvent::KeyPressed(Key::LBracket) => {
+				set_cell_size(app, model, model.config.cell_size / 2)
+			}
+			WindowEvent::KeyPressed(Key::RBracket) => {
+				set_cell_size(app, model, model.config.cell_size * 2)
+			}
 			WindowEvent::KeyPressed(Key::Return) => {
 				model.animation_state = model.animation_state.toggle()
 			}
 			WindowEvent::MouseWheel(MouseScrollDelta::LineDelta(_, delta), _) => {
 				model.brush.size = {
 					let size = (model.brush.size as i32 + delta as i32).max(0).min(10) as u32;
-					if 2usize.pow(size) > (BOARD_WIDTH / 2) || 2usize.pow(size) > (BOARD_HEIGHT / 2)
+					let board = model.world.board();
+					if 2usize.pow(size) > (board.width / 2) || 2usize.pow(size) > (board.height / 2)
 					{
 						model.brush.size
 					} else {
@@ -127,13 +277,150 @@ fn event(app: &App, model: &mut Model, event: Event) {
 	}
 }
 
-fn get_cell_pos_under_pointer(pos: Vec2) -> ColRow {
-	const WINDOW_WIDTH: f32 = BOARD_WIDTH as f32 * CELL_SIZE as f32;
-	const WINDOW_HEIGHT: f32 = BOARD_HEIGHT as f32 * CELL_SIZE as f32;
-	let brush_px_y = (pos.y - WINDOW_HEIGHT * 0.5) * -1.0;
-	let brush_px_x = pos.x + WINDOW_WIDTH * 0.5;
-	let brush_row = (brush_px_y / CELL_SIZE as f32).floor().max(0.0) as usize;
-	let brush_col = (brush_px_x / CELL_SIZE as f32).floor().max(0.0) as usize;
+fn next_growth_strategy(strategy: &GrowthStrategy) -> GrowthStrategy {
+	match strategy {
+		GrowthStrategy::Plurality => GrowthStrategy::WeightedRandom { seed: random() },
+		GrowthStrategy::WeightedRandom { .. } => {
+			GrowthStrategy::Dominance(vec![Ruleset::LIFE, Ruleset::BRIANS_BRAIN, Ruleset::SEEDS])
+		}
+		GrowthStrategy::Dominance(_) => GrowthStrategy::RockPaperScissors(vec![
+			Ruleset::LIFE,
+			Ruleset::BRIANS_BRAIN,
+			Ruleset::SEEDS,
+		]),
+		GrowthStrategy::RockPaperScissors(_) => {
+			let mut strengths = vec![1.0; Ruleset::ANNEAL.index() + 1];
+			strengths[Ruleset::LIFE.index()] = 2.0;
+			strengths[Ruleset::ANNEAL.index()] = 0.5;
+			GrowthStrategy::Strength(strengths)
+		}
+		GrowthStrategy::Strength(_) => GrowthStrategy::Plurality,
+	}
+}
+
+fn next_energy_supply(supply: Option<Supply>) -> Option<Supply> {
+	match supply {
+		None => Some(Supply::Uniform(0.5)),
+		Some(Supply::Uniform(_)) => Some(Supply::Gradient {
+			left: 0.0,
+			right: 1.5,
+		}),
+		Some(Supply::Gradient { .. }) => Some(Supply::Painted),
+		Some(Supply::Painted) => None,
+	}
+}
+
+fn next_update_mode(mode: UpdateMode) -> UpdateMode {
+	match mode {
+		UpdateMode::Synchronous => UpdateMode::RandomSequential { seed: random() },
+		UpdateMode::RandomSequential { .. } => UpdateMode::AlphaAsynchronous {
+			alpha: 0.5,
+			seed: random(),
+		},
+		UpdateMode::AlphaAsynchronous { .. } => UpdateMode::Checkerboard,
+		UpdateMode::Checkerboard => UpdateMode::Synchronous,
+	}
+}
+
+/// Cycles noise off, then uniform, then in blotches, then radiating from
+/// under the brush, keeping its effect and rate
+fn next_noise(noise: Option<&Noise>, brush: &Brush) -> Option<Noise> {
+	let (effect, rate) = noise.map_or((Effect::Flip, NOISE_RATE), |noise| {
+		(noise.effect, noise.rate)
+	});
+	let pattern = match noise.map(|noise| noise.pattern) {
+		None => Pattern::Uniform,
+		Some(Pattern::Uniform) => Pattern::Value { scale: 16.0 },
+		Some(Pattern::Value { .. }) => Pattern::Radial {
+			row: brush.col_row.row,
+			col: brush.col_row.col,
+			radius: (2usize.pow(brush.size) * 4) as f32,
+		},
+		Some(Pattern::Radial { .. }) => return None,
+	};
+	Some(Noise::new(effect, pattern, rate).with_seed(random()))
+}
+
+/// Zooms in or out, rescaling the board to fill the same window with cells
+/// of the new size
+fn set_cell_size(app: &App, model: &mut Model, cell_size: usize) {
+	let cell_size = cell_size.clamp(1, MAX_CELL_SIZE);
+	let board = model.world.board();
+	let width = (board.width * model.config.cell_size / cell_size).max(1);
+	let height = (board.height * model.config.cell_size / cell_size).max(1);
+	model.config.cell_size = cell_size;
+	resize_board(app, model, width, height, Fit::Scale);
+}
+
+/// Shrinks the cells until the board fits a window the window manager made
+/// smaller than asked for, say to fit it on the screen, so that the board
+/// keeps its configured size. Boards too big to fit even with the smallest
+/// cells are cropped.
+fn fit_board_to_window(app: &App, model: &mut Model, size: Vec2) {
+	let board = model.world.board();
+	let (width, height) = (board.width, board.height);
+	let fits = |cell_size: usize| {
+		(width * cell_size) as f32 <= size.x && (height * cell_size) as f32 <= size.y
+	};
+	if fits(model.config.cell_size) {
+		return;
+	}
+	match (1..model.config.cell_size)
+		.rev()
+		.find(|&cell_size| fits(cell_size))
+	{
+		Some(cell_size) => {
+			eprintln!("memento-mori: shrinking cells to {cell_size}px to fit the board on screen");
+			model.config.cell_size = cell_size;
+			resize_board(app, model, width, height, Fit::Crop);
+		}
+		None => {
+			let cropped_width = (size.x.floor().max(1.0) as usize).min(width);
+			let cropped_height = (size.y.floor().max(1.0) as usize).min(height);
+			eprintln!(
+				"memento-mori: cropping the board from {width}x{height} to \
+				 {cropped_width}x{cropped_height} to fit it on screen"
+			);
+			model.config.cell_size = 1;
+			resize_board(app, model, cropped_width, cropped_height, Fit::Crop);
+		}
+	}
+}
+
+/// Resizes the board along with everything that follows its size, and fits
+/// the window around it
+fn resize_board(app: &App, model: &mut Model, width: usize, height: usize, fit: Fit) {
+	let board = model.world.board();
+	if (board.width, board.height) != (width, height) {
+		model.world.resize(width, height, fit);
+		model.graphics = make_graphics(app, width, height);
+		model.brush.stroke = Stroke::new(width * height);
+		model.brush.col_row = get_cell_pos_under_pointer(model, model.brush.pos);
+		model.config.board_width = width;
+		model.config.board_height = height;
+		#[cfg(feature = "nite")]
+		if let Some(oni_manager) = &mut model.oni_manager {
+			oni_manager.fit_to_board(width, height);
+		}
+	}
+
+	let window = app.main_window();
+	let cell_size = model.config.cell_size as f32;
+	let (window_width, window_height) = (width as f32 * cell_size, height as f32 * cell_size);
+	if window.rect().w() != window_width || window.rect().h() != window_height {
+		window.set_inner_size_points(window_width, window_height);
+	}
+}
+
+fn get_cell_pos_under_pointer(model: &Model, pos: Vec2) -> ColRow {
+	let board = model.world.board();
+	let cell_size = model.config.cell_size as f32;
+	let window_width = board.width as f32 * cell_size;
+	let window_height = board.height as f32 * cell_size;
+	let brush_px_y = (pos.y - window_height * 0.5) * -1.0;
+	let brush_px_x = pos.x + window_width * 0.5;
+	let brush_row = (brush_px_y / cell_size).floor().max(0.0) as usize;
+	let brush_col = (brush_px_x / cell_size).floor().max(0.0) as usize;
 
 	// You would think you wouldn't have to clamp the row and col to the board
 	// dimensions because when the mouse is off the board it doesn't produce
@@ -141,18 +428,20 @@ fn get_cell_pos_under_pointer(pos: Vec2) -> ColRow {
 	// you click and drag you can maintain focus on the window while pulling
 	// the pointer out of the window.
 	ColRow {
-		col: brush_col.max(0).min(BOARD_WIDTH - 1),
-		row: brush_row.max(0).min(BOARD_HEIGHT - 1),
+		col: brush_col.max(0).min(board.width - 1),
+		row: brush_row.max(0).min(board.height - 1),
 	}
 }
 
-fn paint(model: &mut Model, f: fn(&mut World, &Brush, usize)) {
-	const WINDOW_WIDTH: f32 = BOARD_WIDTH as f32 * CELL_SIZE as f32;
-	const WINDOW_HEIGHT: f32 = BOARD_HEIGHT as f32 * CELL_SIZE as f32;
-	let brush_px_y = (model.brush.pos.y - WINDOW_HEIGHT * 0.5) * -1.0;
-	let brush_px_x = model.brush.pos.x + WINDOW_WIDTH * 0.5;
-	let brush_row = (brush_px_y / CELL_SIZE as f32).floor().max(0.0) as usize;
-	let brush_col = (brush_px_x / CELL_SIZE as f32).floor().max(0.0) as usize;
+fn paint(model: &mut Model, f: fn(&mut World, &mut Brush, usize)) {
+	let (board_width, board_height) = (model.world.board().width, model.world.board().height);
+	let cell_size = model.config.cell_size as f32;
+	let window_width = board_width as f32 * cell_size;
+	let window_height = board_height as f32 * cell_size;
+	let brush_px_y = (model.brush.pos.y - window_height * 0.5) * -1.0;
+	let brush_px_x = model.brush.pos.x + window_width * 0.5;
+	let brush_row = (brush_px_y / cell_size).floor().max(0.0) as usize;
+	let brush_col = (brush_px_x / cell_size).floor().max(0.0) as usize;
 
 	let brush_size = 2usize.pow(model.brush.size);
 
@@ -161,46 +450,51 @@ fn paint(model: &mut Model, f: fn(&mut World, &Brush, usize)) {
 	} else {
 		0
 	};
-	let max_row = (brush_row + brush_size).min(BOARD_HEIGHT - 1);
+	let max_row = (brush_row + brush_size).min(board_height - 1);
 
 	let min_col = if brush_col > brush_size {
 		brush_col - brush_size
 	} else {
 		0
 	};
-	let max_col = (brush_col + brush_size).min(BOARD_WIDTH - 1);
+	let max_col = (brush_col + brush_size).min(board_width - 1);
 
 	for check_row in min_row..=max_row {
-		let check_px_y = (check_row as f32 + 0.5) * CELL_SIZE as f32;
+		let check_px_y = (check_row as f32 + 0.5) * cell_size;
 		for check_col in min_col..=max_col {
-			let check_px_x = (check_col as f32 + 0.5) * CELL_SIZE as f32;
+			let check_px_x = (check_col as f32 + 0.5) * cell_size;
 			let inside = (check_px_x - brush_px_x).pow(2) + (check_px_y - brush_px_y).pow(2)
-				< (brush_size as f32 * CELL_SIZE as f32 - CELL_SIZE as f32 * 0.25).pow(2);
+				< (brush_size as f32 * cell_size - cell_size * 0.25).pow(2);
 			if inside {
-				let idx = check_row * BOARD_WIDTH + check_col;
-				f(&mut model.world, &model.brush, idx);
+				let idx = check_row * board_width + check_col;
+				f(&mut model.world, &mut model.brush, idx);
 			}
 		}
 	}
 }
 
 fn update(app: &App, model: &mut Model, _update: Update) {
+	model.window_shown = true;
 	let advance_simulation =
-		model.is_running() && model.last_generation_at.elapsed() >= GENERATION_RATE;
+		model.is_running() && model.last_generation_at.elapsed() >= model.config.generation_rate;
 
-	fn paint_liveness(world: &mut World, brush: &Brush, idx: usize) {
-		let board = world.board_mut();
-		let brush_idx = brush.col_row.row * BOARD_WIDTH + brush.col_row.col;
-		let on = board[brush_idx].ruleset.on();
+	fn paint_liveness(world: &mut World, brush: &mut Brush, idx: usize) {
+		let (board, rules) = world.board_mut_and_rules();
+		let brush_idx = brush.col_row.row * board.width + brush.col_row.col;
 		if board[idx].ruleset == board[brush_idx].ruleset {
-			board[idx] = on;
+			let first_touch = brush.stroke.touch(idx);
+			board[idx] = rules.paint(board[idx], first_touch);
 		}
 	}
 
-	fn paint_ruleset(world: &mut World, brush: &Brush, idx: usize) {
-		let (board, next_board) = world.this_board_and_next();
-		board[idx].ruleset = brush.ruleset;
-		next_board[idx].ruleset = brush.ruleset;
+	fn paint_ruleset(world: &mut World, brush: &mut Brush, idx: usize) {
+		world.set_ruleset(idx, brush.ruleset);
+	}
+
+	fn paint_energy_supply(world: &mut World, _brush: &mut Brush, idx: usize) {
+		if let Some(energy) = world.energy_mut() {
+			energy.paint_supply(idx, PAINTED_ENERGY_SUPPLY);
+		}
 	}
 
 	#[cfg(feature = "nite")]
@@ -210,19 +504,21 @@ fn update(app: &App, model: &mut Model, _update: Update) {
 				println!("Error updating: {e:?}");
 			} else {
 				if oni_manager.is_anyone_here() {
-					model.world.temporary_rulesets.fill(None);
-					model.world.temporary_states.fill(None);
-					for row in 0..BOARD_HEIGHT {
-						for col in 0..BOARD_WIDTH {
-							let pct_y = row as f32 / (BOARD_HEIGHT - 1) as f32;
-							let pct_x = col as f32 / (BOARD_WIDTH - 1) as f32;
-							let board_idx = row * BOARD_WIDTH + col;
+					model.world.clear_temporary_rulesets();
+					model.world.clear_temporary_states();
+					let (width, height) = (model.world.board().width, model.world.board().height);
+					for row in 0..height {
+						for col in 0..width {
+							let pct_y = row as f32 / (height - 1) as f32;
+							let pct_x = col as f32 / (width - 1) as f32;
+							let board_idx = row * width + col;
 							if oni_manager.user_at_coords(pct_x, pct_y) > 0 {
-								model.world.temporary_rulesets[board_idx] =
-									Some(model.brush.ruleset);
+								model
+									.world
+									.set_temporary_ruleset(board_idx, model.brush.ruleset);
 								if oni_manager.state_at_coords(pct_x, pct_y) > 100 {
-									model.world.temporary_states[board_idx] =
-										Some(model.brush.ruleset.on().state)
+									let state = model.world.rules().on(model.brush.ruleset).state;
+									model.world.set_temporary_state(board_idx, state);
 								}
 							}
 						}
@@ -235,6 +531,16 @@ fn update(app: &App, model: &mut Model, _update: Update) {
 	if advance_simulation {
 		model.world.generate(model.growth);
 		model.world.swap();
+		for detector in model.world.equilibrium_detectors() {
+			if let Some(equilibrium) = detector.equilibrium().filter(|_| detector.just_settled()) {
+				println!(
+					"{:?} settled into period {} at generation {}",
+					detector.scope(),
+					equilibrium.period,
+					equilibrium.since
+				);
+			}
+		}
 		model.last_generation_at = Instant::now();
 		model.animation_state = model.animation_state.next();
 	}
@@ -243,32 +549,22 @@ fn update(app: &App, model: &mut Model, _update: Update) {
 		#[cfg(feature = "nite")]
 		if let Some(oni_manager) = &mut model.oni_manager {
 			if oni_manager.is_anyone_here() {
-				let (board, next_board, temporary_rulesets, temporary_states) =
-					model.world.this_board_and_next_and_temporary();
-				for (((cell, next_cell), maybe_ruleset), maybe_state) in board
-					.iter_mut()
-					.zip(next_board.iter_mut())
-					.zip(temporary_rulesets)
-					.zip(temporary_states)
-				{
-					if let Some(ruleset) = maybe_ruleset {
-						cell.ruleset = *ruleset;
-						next_cell.ruleset = *ruleset;
-					}
-					if let Some(state) = maybe_state {
-						next_cell.state = *state;
-					}
-				}
+				model.world.commit_temporary_rulesets();
 			}
 		}
 		model.draw_user_state = DrawUserState::None;
-		model.world.temporary_rulesets.fill(None);
+		model.world.clear_temporary_rulesets();
 	}
 
 	if app.mouse.buttons.left().is_down() {
 		paint(model, paint_liveness);
-	} else if app.mouse.buttons.right().is_down() {
-		paint(model, paint_ruleset);
+	} else {
+		model.brush.stroke.end();
+		if app.mouse.buttons.right().is_down() {
+			paint(model, paint_ruleset);
+		} else if app.mouse.buttons.middle().is_down() {
+			paint(model, paint_energy_supply);
+		}
 	}
 
 	if advance_simulation && model.record_frames > 0 {
@@ -282,7 +578,14 @@ fn update(app: &App, model: &mut Model, _update: Update) {
 fn view(app: &App, model: &Model, frame: Frame) {
 	let board = model.world.board();
 
-	render_graphics(&frame, &model.graphics, &model.world, app.keys.mods.ctrl());
+	render_graphics(
+		&frame,
+		&model.graphics,
+		&model.world,
+		app.keys.mods.ctrl(),
+		model.energy_heatmap,
+		model.activity_overlay,
+	);
 
 	if model.capture_frame {
 		println!("capture {}", model.record_frames);
@@ -298,7 +601,7 @@ fn view(app: &App, model: &Model, frame: Frame) {
 
 	if model.draw_brush && !model.capture_frame {
 		let draw = app.draw();
-		let brush_radius = (2usize.pow(model.brush.size as u32) * CELL_SIZE) as f32;
+		let brush_radius = (2usize.pow(model.brush.size as u32) * model.config.cell_size) as f32;
 		draw.ellipse()
 			.radius(brush_radius)
 			.xy(model.brush.pos)
@@ -309,13 +612,13 @@ fn view(app: &App, model: &Model, frame: Frame) {
 		let wr = app.main_window().rect();
 		if wr.contains(model.brush.pos) {
 			let ColRow { col, row } = model.brush.col_row;
-			let brush_idx = row * BOARD_WIDTH + col;
-			if brush_idx < BOARD_WIDTH * BOARD_HEIGHT {
+			let brush_idx = row * board.width + col;
+			if brush_idx < board.cells.len() {
 				let cell = board[brush_idx];
 				let wr = wr.pad(20.0);
 
 				{
-					let text = cell.ruleset.write_debug(cell);
+					let text = model.world.rules().write_debug(cell);
 					let text_width = (text.len() * 6) as f32;
 					draw.rect()
 						.color(BLACK)
@@ -326,9 +629,38 @@ fn view(app: &App, model: &Model, frame: Frame) {
 				}
 
 				{
-					let growth_text = if model.growth { "on" } else { "off" };
-					let text =
-						format!("Growth {}; Painting {:?}", growth_text, model.brush.ruleset);
+					let growth_text = if model.growth {
+						format!(
+							"{}, {:?} cells claim",
+							model.world.growth_strategy(),
+							model.world.territory_claims()
+						)
+					} else {
+						"off".to_string()
+					};
+					let decay_text = match model.world.territory_decay() {
+						Some(decay) => format!(
+							"to {} after {}",
+							model.world.rules().name(decay.background),
+							decay.generations
+						),
+						None => "off".to_string(),
+					};
+					let noise_text = match model.world.noise() {
+						Some(noise) => {
+							format!("{:?} {:?} at {}", noise.effect, noise.pattern, noise.rate)
+						}
+						None => "off".to_string(),
+					};
+					let text = format!(
+						"Growth {}; Decay {}; Noise {}; Boundary {:?}; Painting {} ({:?})",
+						growth_text,
+						decay_text,
+						noise_text,
+						model.world.boundary(),
+						model.world.rules().name(model.brush.ruleset),
+						model.world.ruleset_update_mode(model.brush.ruleset)
+					);
 					let text_width = (text.len() * 6) as f32;
 					draw.rect()
 						.color(BLACK)
@@ -3,8 +3,10 @@ mod oni_manager;
 #[cfg(feature = "nite")]
 pub use oni_manager::OniManager;
 
+use crate::config::Config;
 use crate::graphics::Graphics;
 use crate::rules::Ruleset;
+use crate::stroke::Stroke;
 use crate::world::World;
 use nannou::prelude::*;
 use std::time::Instant;
@@ -21,6 +23,7 @@ pub struct Brush {
 	pub pos: Vec2,
 	pub ruleset: Ruleset,
 	pub col_row: ColRow,
+	pub stroke: Stroke,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -75,7 +78,13 @@ pub struct Model {
 	pub brush: Brush,
 	pub draw_brush: bool,
 	pub growth: bool,
+	/// Whether to draw the energy field instead of the cells
+	pub energy_heatmap: bool,
+	/// Whether to tint the tiles that were worked out in the last generation
+	pub activity_overlay: bool,
 	pub graphics: Graphics,
+	/// Kept up to date with the board's size as it's resized
+	pub config: Config,
 	pub animation_state: AnimationState,
 	pub last_generation_at: Instant,
 	#[cfg(feature = "nite")]
@@ -83,6 +92,9 @@ pub struct Model {
 	pub draw_user_state: DrawUserState,
 	pub record_frames: u16,
 	pub capture_frame: bool,
+	/// Whether the first frame has been updated. Resizes before then come
+	/// from the window manager fitting the window on screen, not the user.
+	pub window_shown: bool,
 }
 
 impl Model {
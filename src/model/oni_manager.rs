@@ -102,7 +102,38 @@ impl OniManager {
 			println!("Failed to set depth/color registration: {:?}", e);
 		}
 
-		let (stream_offset_x, stream_offset_y, stream_scale_x, stream_scale_y) = {
+		let mut oni_manager = OniManager {
+			device: default_device,
+			depth_stream,
+			user_tracker: Box::leak(Box::new(user_tracker)),
+			user_map: vec![0; stream_width * stream_height].leak(),
+			users_present: false,
+			stream_width,
+			stream_height,
+			stream_offset_x: 0.0,
+			stream_offset_y: 0.0,
+			stream_scale_x: 1.0,
+			stream_scale_y: 1.0,
+			color_stream: Box::leak(Box::new(color_stream)),
+			color_frame: vec![
+				0;
+				color_mode.resolution_x as usize * color_mode.resolution_y as usize
+			]
+			.leak(),
+		};
+		oni_manager.fit_to_board(board_width, board_height);
+		Ok(oni_manager)
+	}
+
+	/// Lines the stream up with a board of a new size
+	pub fn fit_to_board(&mut self, board_width: usize, board_height: usize) {
+		let (stream_width, stream_height) = (self.stream_width, self.stream_height);
+		(
+			self.stream_offset_x,
+			self.stream_offset_y,
+			self.stream_scale_x,
+			self.stream_scale_y,
+		) = {
 			let board_ratio = board_width as f32 / board_height as f32;
 			let stream_ratio = stream_width as f32 / stream_height as f32;
 
@@ -131,26 +162,6 @@ impl OniManager {
 		// 	board_width, board_height, stream_width, stream_height,
 		// 	stream_offset_x, stream_offset_y, stream_scale_x, stream_scale_y
 		// );
-
-		Ok(OniManager {
-			device: default_device,
-			depth_stream,
-			user_tracker: Box::leak(Box::new(user_tracker)),
-			user_map: vec![0; stream_width * stream_height].leak(),
-			users_present: false,
-			stream_width,
-			stream_height,
-			stream_offset_x,
-			stream_offset_y,
-			stream_scale_x,
-			stream_scale_y,
-			color_stream: Box::leak(Box::new(color_stream)),
-			color_frame: vec![
-				0;
-				color_mode.resolution_x as usize * color_mode.resolution_y as usize
-			]
-			.leak(),
-		})
 	}
 }
 
@@ -0,0 +1,197 @@
+use crate::rules::{Registry, Ruleset, Vitality};
+use crate::world::{mix, unit, Board};
+
+/// What noise does to the cells it hits
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Effect {
+	/// Live cells die and dead cells come alive
+	Flip,
+	/// Live cells starve, and dead cells are left alone
+	Kill,
+	/// Dead cells come alive, and live cells are left alone
+	Birth,
+}
+
+/// How strongly noise hits each part of the board, from 0 to 1
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Pattern {
+	Uniform,
+	/// Smooth blotches about `scale` cells across
+	Value {
+		scale: f32,
+	},
+	/// Strongest at a point, fading out to nothing `radius` cells away
+	Radial {
+		row: usize,
+		col: usize,
+		radius: f32,
+	},
+}
+
+/// A source of noise that hits cells at random every generation. Each cell
+/// is hit with a chance of `rate`, scaled by the pattern and by the rate of
+/// its ruleset. The dice are rolled from the seed and the cell's place in
+/// space and time, so the same noise can be played back.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Noise {
+	pub effect: Effect,
+	pub pattern: Pattern,
+	pub rate: f32,
+	pub seed: u64,
+	/// How much noise each ruleset takes, indexed by ruleset id. Rulesets
+	/// without one take all of it.
+	ruleset_rates: Vec<f32>,
+}
+
+impl Noise {
+	pub fn new(effect: Effect, pattern: Pattern, rate: f32) -> Noise {
+		Noise {
+			effect,
+			pattern,
+			rate,
+			seed: 0,
+			ruleset_rates: Vec::new(),
+		}
+	}
+
+	pub fn with_seed(mut self, seed: u64) -> Noise {
+		self.seed = seed;
+		self
+	}
+
+	pub fn ruleset_rate(&self, ruleset: Ruleset) -> f32 {
+		self.ruleset_rates
+			.get(ruleset.index())
+			.copied()
+			.unwrap_or(1.0)
+	}
+
+	pub fn set_ruleset_rate(&mut self, ruleset: Ruleset, rate: f32) {
+		if self.ruleset_rates.len() <= ruleset.index() {
+			self.ruleset_rates.resize(ruleset.index() + 1, 1.0);
+		}
+		self.ruleset_rates[ruleset.index()] = rate;
+	}
+
+	/// How strongly the pattern hits a cell
+	pub fn intensity(&self, row: usize, col: usize) -> f32 {
+		match self.pattern {
+			Pattern::Uniform => 1.0,
+			Pattern::Value { scale } => {
+				let y = row as f32 / scale.max(1.0);
+				let x = col as f32 / scale.max(1.0);
+				let (top, left) = (y.floor() as usize, x.floor() as usize);
+				let ty = smoothstep(y.fract());
+				let tx = smoothstep(x.fract());
+				let lattice = |row, col| unit(mix(self.seed ^ VALUE_NOISE, 0, row, col));
+				let upper = lerp(lattice(top, left), lattice(top, left + 1), tx);
+				let lower = lerp(lattice(top + 1, left), lattice(top + 1, left + 1), tx);
+				lerp(upper, lower, ty)
+			}
+			Pattern::Radial {
+				row: center_row,
+				col: center_col,
+				radius,
+			} => {
+				let dy = row as f32 - center_row as f32;
+				let dx = col as f32 - center_col as f32;
+				(1.0 - (dx * dx + dy * dy).sqrt() / radius).max(0.0)
+			}
+		}
+	}
+
+	/// Hits the cells of a freshly generated board. Cells under a temporary
+	/// ruleset are left alone.
+	pub fn apply(
+		&self,
+		rules: &Registry,
+		board: &mut Board,
+		temporary_rulesets: &[Option<Ruleset>],
+	) {
+		for row in 0..board.height {
+			for col in 0..board.width {
+				let idx = row * board.width + col;
+				let cell = board[idx];
+				if temporary_rulesets[idx].is_some() {
+					continue;
+				}
+
+				let chance = self.rate * self.intensity(row, col) * self.ruleset_rate(cell.ruleset);
+				if unit(mix(self.seed, board.generation, row, col)) >= chance {
+					continue;
+				}
+
+				let alive = rules.export(cell) == Vitality::Alive;
+				board[idx] = match (self.effect, alive) {
+					(Effect::Flip | Effect::Kill, true) => rules.starve(cell),
+					(Effect::Flip | Effect::Birth, false) => {
+						rules.import(cell.ruleset, Vitality::Alive)
+					}
+					_ => cell,
+				};
+			}
+		}
+	}
+}
+
+/// Keeps the lattice of value noise apart from the per-cell dice
+const VALUE_NOISE: u64 = 0x006e_6f69_7365;
+
+fn smoothstep(t: f32) -> f32 {
+	t * t * (3.0 - 2.0 * t)
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+	from + (to - from) * t
+}
+
+#[cfg(test)]
+mod test {
+	use super::{Effect, Noise, Pattern};
+	use crate::rules::Ruleset;
+	use crate::world::World;
+
+	#[test]
+	fn noise_hits_cells_by_rate() {
+		let mut world = World::new(8, 8);
+		for idx in 32..64 {
+			world.set_ruleset(idx, Ruleset::SEEDS);
+		}
+		let mut noise = Noise::new(Effect::Birth, Pattern::Uniform, 1.0).with_seed(7);
+		noise.set_ruleset_rate(Ruleset::SEEDS, 0.0);
+
+		let (board, rules) = world.board_mut_and_rules();
+		noise.apply(rules, board, &[None; 64]);
+		assert!(board.cells[..32].iter().all(|cell| cell.state & 0b01 > 0));
+		assert!(board.cells[32..].iter().all(|cell| cell.state == 0));
+
+		noise.effect = Effect::Kill;
+		noise.apply(rules, board, &[None; 64]);
+		assert!(board.iter().all(|cell| cell.state & 0b01 == 0));
+	}
+
+	#[test]
+	fn patterns_fade_between_zero_and_one() {
+		let radial = Noise::new(
+			Effect::Flip,
+			Pattern::Radial {
+				row: 4,
+				col: 4,
+				radius: 4.0,
+			},
+			1.0,
+		);
+		assert_eq!(radial.intensity(4, 4), 1.0);
+		assert_eq!(radial.intensity(4, 6), 0.5);
+		assert_eq!(radial.intensity(0, 0), 0.0);
+
+		let value = Noise::new(Effect::Flip, Pattern::Value { scale: 4.0 }, 1.0);
+		for row in 0..16 {
+			for col in 0..16 {
+				let intensity = value.intensity(row, col);
+				assert!((0.0..1.0).contains(&intensity));
+				assert_eq!(intensity, value.intensity(row, col));
+			}
+		}
+	}
+}
@@ -0,0 +1,58 @@
+use crate::rules::{LifeLike, Rule, Visibility};
+use crate::world::Board;
+use nannou::color::LinSrgba;
+use nannou::rand;
+use std::fmt::Write;
+
+#[derive(Debug)]
+pub struct Anneal;
+impl Anneal {
+	pub const RULE: LifeLike = LifeLike::new(&[4, 6, 7, 8], &[3, 5, 6, 7, 8]);
+}
+
+impl Rule for Anneal {
+	fn on(&self) -> u8 {
+		State::Alive as u8
+	}
+
+	fn off(&self) -> u8 {
+		State::Dead as u8
+	}
+
+	fn random(&self) -> u8 {
+		rand::random_range(0, 2)
+	}
+
+	fn life_like(&self) -> Option<LifeLike> {
+		Some(Anneal::RULE)
+	}
+
+	fn color(&self, state: u8) -> LinSrgba {
+		match state {
+			0b01 => LinSrgba::new(1.0, 0.9, 0.5, 1.0),
+			0b11 => LinSrgba::new(1.0, 0.6, 0.0, 1.0),
+			0b00 => LinSrgba::new(0.0, 0.0, 0.0, 1.0),
+			0b10 => LinSrgba::new(0.3, 0.1, 0.0, 1.0),
+			_ => LinSrgba::new(0.0, 0.0, 0.0, 1.0),
+		}
+	}
+
+	fn rule_color(&self) -> LinSrgba {
+		LinSrgba::new(1.0, 0.6, 0.0, 0.125)
+	}
+
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		Anneal::RULE.next_state(board, row, col, visibility)
+	}
+
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
+		write!(output, "{:02b}", state)
+	}
+}
+
+#[derive(PartialEq, Eq)]
+#[repr(u8)]
+enum State {
+	Dead = 0,
+	Alive = 1,
+}
@@ -1,34 +1,28 @@
-use crate::rules::Ruleset;
-use crate::world::{Board, Cell};
+use crate::rules::life_like::{count_live_neighbors, MOORE_NEIGHBORHOOD};
+use crate::rules::{Rule, Visibility};
+use crate::world::{Board, Boundary};
 use nannou::color::LinSrgba;
 use nannou::rand;
 use std::fmt::Write;
 
+#[derive(Debug)]
 pub struct AntiLife;
-impl AntiLife {
-	pub fn alive() -> Cell {
-		Cell {
-			ruleset: Ruleset::AntiLife,
-			state: State::Alive as u8,
-		}
+
+impl Rule for AntiLife {
+	fn on(&self) -> u8 {
+		State::Dead as u8
 	}
 
-	pub fn dead() -> Cell {
-		Cell {
-			ruleset: Ruleset::AntiLife,
-			state: State::Dead as u8,
-		}
+	fn off(&self) -> u8 {
+		State::Alive as u8
 	}
 
-	pub fn random() -> Cell {
-		Cell {
-			ruleset: Ruleset::AntiLife,
-			state: rand::random(),
-		}
+	fn random(&self) -> u8 {
+		rand::random()
 	}
 
-	pub fn color(cell: Cell) -> LinSrgba {
-		match cell.state {
+	fn color(&self, state: u8) -> LinSrgba {
+		match state {
 			0b11 => LinSrgba::new(1.0, 1.0, 1.0, 1.0),
 			// 0b10 => LinSrgba::new(0.0, 0.0, 1.0, 1.0),
 			0b10 => LinSrgba::new(1.0, 0.9, 0.8, 1.0),
@@ -39,11 +33,15 @@ impl AntiLife {
 		}
 	}
 
-	pub fn next_cell_state(board: &Board, row: usize, col: usize) -> Cell {
-		next_cell_state(board, row, col)
+	fn rule_color(&self) -> LinSrgba {
+		LinSrgba::new(0.0, 1.0, 0.0, 0.125)
 	}
 
-	pub fn write_debug<W: Write>(output: &mut W, state: u8) -> std::fmt::Result {
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		next_cell_state(board, row, col, visibility)
+	}
+
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
 		write!(output, "{:02b}", state)
 	}
 }
@@ -55,58 +53,29 @@ enum State {
 	Alive = 1,
 }
 
-fn count_live_row_neighbors(board: &Board, row: usize, col: usize, exclude_center: bool) -> u8 {
-	let idx = row * board.width + col;
-
-	let mut live = if exclude_center {
-		1
-	} else {
-		board[idx].state & 0b01
-	};
-
-	live <<= 1;
-	if col > 0 {
-		live |= board[idx - 1].state & 0b01;
-	} else {
-		live |= 0b01;
+/// Anti-Life's quiet background is made of live cells, so past the edge of a
+/// dead boundary the board carries on as background, instead of eating away
+/// at the edges.
+fn count_neighbors(board: &Board, row: usize, col: usize, visibility: Visibility) -> u32 {
+	if board.boundary != Boundary::Dead || !board.is_edge(row, col) {
+		return count_live_neighbors(board, row, col, visibility);
 	}
-
-	live <<= 1;
-	if (col + 1) < board.width {
-		live |= board[idx + 1].state & 0b01;
-	} else {
-		live |= 0b01;
-	}
-
-	live
-}
-
-fn count_live_neighbors(board: &Board, row: usize, col: usize) -> u32 {
-	let mut live_neighbors = count_live_row_neighbors(board, row, col, true);
-
-	live_neighbors <<= 3;
-	if row > 0 {
-		live_neighbors |= count_live_row_neighbors(board, row - 1, col, false);
-	} else {
-		live_neighbors |= 0b111;
-	}
-
-	live_neighbors <<= 3;
-	if row + 1 < board.height {
-		live_neighbors |= count_live_row_neighbors(board, row + 1, col, false);
-	} else {
-		live_neighbors |= 0b111;
-	}
-
-	live_neighbors.count_ones()
+	let live = MOORE_NEIGHBORHOOD
+		.iter()
+		.map(|&(dr, dc)| match board.neighbor_idx(row, col, dr, dc) {
+			Some(idx) => visibility.live(board[idx]),
+			None => 1,
+		})
+		.sum::<i32>();
+	live.clamp(0, 8) as u32
 }
 
-fn next_cell_state(board: &Board, row: usize, col: usize) -> Cell {
-	let live_neighbors = count_live_neighbors(board, row, col);
+fn next_cell_state(board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+	let live_neighbors = count_neighbors(board, row, col, visibility);
 	let idx = row * board.width + col;
 
 	let is_alive = board[idx].state & 0b01 > 0;
-	let state = if is_alive {
+	if is_alive {
 		if live_neighbors != 5 {
 			State::Alive as u8 | 0b10
 		} else {
@@ -118,10 +87,5 @@ fn next_cell_state(board: &Board, row: usize, col: usize) -> Cell {
 		} else {
 			State::Dead as u8
 		}
-	};
-
-	Cell {
-		ruleset: Ruleset::AntiLife,
-		state,
 	}
 }
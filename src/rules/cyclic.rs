@@ -0,0 +1,304 @@
+use crate::rules::{Neighborhood, ParseRuleError, Rule, Visibility, Vitality};
+use crate::world::Board;
+use nannou::color::LinSrgba;
+use nannou::rand;
+use std::fmt::Write;
+
+/// Cyclic cellular automaton. Every cell holds one of `states` colors, and
+/// advances to the next color (wrapping around) when at least `threshold`
+/// neighbors already hold it. Random boards organize themselves into
+/// rotating spirals.
+///
+/// The color is stored in the upper bits. The lowest bit is set on cells
+/// that advanced this generation, so only the wavefronts count as live.
+#[derive(Clone, PartialEq)]
+pub struct Cyclic {
+	states: u8,
+	threshold: u8,
+	radius: u8,
+	neighborhood: Neighborhood,
+	gradient: Vec<[f32; 3]>,
+}
+
+impl Cyclic {
+	pub const MAX_STATES: u8 = 127;
+
+	pub const THREE_ONE_THREE: Cyclic = Cyclic::new(3, 3, 1, Neighborhood::Moore);
+	pub const CCA: Cyclic = Cyclic::new(14, 1, 1, Neighborhood::VonNeumann);
+	pub const SPIRALS: Cyclic = Cyclic::new(8, 5, 3, Neighborhood::Moore);
+
+	pub const fn new(states: u8, threshold: u8, radius: u8, neighborhood: Neighborhood) -> Cyclic {
+		Cyclic {
+			states,
+			threshold,
+			radius,
+			neighborhood,
+			gradient: Vec::new(),
+		}
+	}
+
+	/// Sets the colors the states are spread over. The gradient wraps around
+	/// from the last color back to the first, like the states do.
+	pub fn with_gradient(mut self, gradient: Vec<[f32; 3]>) -> Cyclic {
+		self.gradient = gradient;
+		self
+	}
+
+	/// Parses a rule like `R1/T3/C3/NM`: range, threshold, number of colors,
+	/// and an optional Moore (`NM`) or von Neumann (`NN`) neighborhood.
+	pub fn parse(rule: &str) -> Result<Cyclic, ParseRuleError> {
+		let mut radius = None;
+		let mut threshold = None;
+		let mut states = None;
+		let mut neighborhood = Neighborhood::Moore;
+
+		for part in rule.trim().split('/') {
+			let part = part.trim();
+			let value = part.get(1..).unwrap_or_default();
+			match part.chars().next() {
+				Some('R' | 'r') => radius = Some(parse_segment(value, 'R', 1..=10)?),
+				Some('T' | 't') => threshold = Some(parse_segment(value, 'T', 1..=u8::MAX)?),
+				Some('C' | 'c') => {
+					states = Some(
+						parse_segment(value, 'C', 2..=Cyclic::MAX_STATES)
+							.map_err(|_| ParseRuleError::InvalidStateCount)?,
+					)
+				}
+				Some('N' | 'n') => {
+					neighborhood = match value {
+						"M" | "m" => Neighborhood::Moore,
+						"N" | "n" => Neighborhood::VonNeumann,
+						_ => return Err(ParseRuleError::InvalidSegment('N')),
+					}
+				}
+				Some(c) => return Err(ParseRuleError::UnexpectedCharacter(c)),
+				None => return Err(ParseRuleError::Empty),
+			}
+		}
+
+		Ok(Cyclic::new(
+			states.ok_or(ParseRuleError::MissingStateCount)?,
+			threshold.ok_or(ParseRuleError::InvalidSegment('T'))?,
+			radius.unwrap_or(1),
+			neighborhood,
+		))
+	}
+
+	fn color_of(&self, state: u8) -> u8 {
+		(state >> 1) % self.states
+	}
+
+	/// Counts the neighbors holding `color`, weighted by how visible their
+	/// rulesets are. Cells off the edge of a dead or live boundary don't hold
+	/// any color.
+	fn count_neighbors_with_color(
+		&self,
+		board: &Board,
+		row: usize,
+		col: usize,
+		color: u8,
+		visibility: Visibility,
+	) -> i32 {
+		let radius = self.radius as isize;
+
+		let mut count = 0;
+		for dr in -radius..=radius {
+			let reach = match self.neighborhood {
+				Neighborhood::Moore => radius,
+				Neighborhood::VonNeumann => radius - dr.abs(),
+			};
+			for dc in -reach..=reach {
+				if let Some(idx) = board.neighbor_idx(row, col, dr, dc) {
+					if self.color_of(board[idx].state) == color {
+						count += visibility.weight(board[idx].ruleset) as i32;
+					}
+				}
+			}
+		}
+
+		// The cell itself never holds the next color
+		count
+	}
+
+	pub fn next_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		let color = self.color_of(board[row * board.width + col].state);
+		let next_color = (color + 1) % self.states;
+		let count = self.count_neighbors_with_color(board, row, col, next_color, visibility);
+		if count >= self.threshold as i32 {
+			next_color << 1 | 0b01
+		} else {
+			color << 1
+		}
+	}
+}
+
+impl Rule for Cyclic {
+	fn on(&self) -> u8 {
+		0b01
+	}
+
+	fn off(&self) -> u8 {
+		0b00
+	}
+
+	fn random(&self) -> u8 {
+		rand::random_range(0, self.states) << 1
+	}
+
+	fn radius(&self) -> usize {
+		self.radius as usize
+	}
+
+	/// Every cell holds some color, so only the wavefronts claim territory
+	fn is_present(&self, state: u8) -> bool {
+		state & 0b01 > 0
+	}
+
+	fn export(&self, state: u8) -> Vitality {
+		if self.is_present(state) {
+			Vitality::Alive
+		} else {
+			Vitality::Dead
+		}
+	}
+
+	/// Live cells arrive as a wavefront of the first color
+	fn import(&self, vitality: Vitality) -> u8 {
+		match vitality {
+			Vitality::Alive => 0b01,
+			_ => 0b00,
+		}
+	}
+
+	/// Starving wavefronts stop advancing, but keep their color
+	fn starve(&self, state: u8) -> u8 {
+		state & !0b01
+	}
+
+	fn color(&self, state: u8) -> LinSrgba {
+		let gradient = if self.gradient.is_empty() {
+			DEFAULT_GRADIENT
+		} else {
+			&self.gradient
+		};
+
+		let position = self.color_of(state) as f32 / self.states as f32 * gradient.len() as f32;
+		let from = gradient[position as usize % gradient.len()];
+		let to = gradient[(position as usize + 1) % gradient.len()];
+		let t = position.fract();
+		LinSrgba::new(
+			from[0] + (to[0] - from[0]) * t,
+			from[1] + (to[1] - from[1]) * t,
+			from[2] + (to[2] - from[2]) * t,
+			1.0,
+		)
+	}
+
+	fn rule_color(&self) -> LinSrgba {
+		LinSrgba::new(1.0, 1.0, 0.0, 0.125)
+	}
+
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		self.next_state(board, row, col, visibility)
+	}
+
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
+		write!(output, "{}", self.color_of(state))
+	}
+}
+
+impl std::fmt::Display for Cyclic {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(
+			fmt,
+			"R{}/T{}/C{}/N{}",
+			self.radius,
+			self.threshold,
+			self.states,
+			match self.neighborhood {
+				Neighborhood::Moore => "M",
+				Neighborhood::VonNeumann => "N",
+			}
+		)
+	}
+}
+
+impl std::fmt::Debug for Cyclic {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		std::fmt::Display::fmt(self, fmt)
+	}
+}
+
+impl std::str::FromStr for Cyclic {
+	type Err = ParseRuleError;
+	fn from_str(rule: &str) -> Result<Self, Self::Err> {
+		Cyclic::parse(rule)
+	}
+}
+
+const DEFAULT_GRADIENT: &[[f32; 3]] = &[
+	[0.9, 0.1, 0.3],
+	[1.0, 0.7, 0.1],
+	[0.2, 0.8, 0.4],
+	[0.1, 0.4, 1.0],
+	[0.6, 0.1, 0.9],
+];
+
+fn parse_segment(
+	value: &str,
+	segment: char,
+	valid: std::ops::RangeInclusive<u8>,
+) -> Result<u8, ParseRuleError> {
+	value
+		.parse::<u8>()
+		.ok()
+		.filter(|value| valid.contains(value))
+		.ok_or(ParseRuleError::InvalidSegment(segment))
+}
+
+#[cfg(test)]
+mod test {
+	use super::Cyclic;
+	use crate::rules::{Neighborhood, ParseRuleError, Visibility};
+	use crate::world::{Board, Boundary, Cell};
+
+	#[test]
+	fn parse_reads_range_threshold_and_colors() {
+		assert_eq!(Cyclic::parse("R1/T3/C3/NM"), Ok(Cyclic::THREE_ONE_THREE));
+		assert_eq!(Cyclic::parse("R1/T1/C14/NN"), Ok(Cyclic::CCA));
+		assert_eq!(Cyclic::parse("T1/C14/NN"), Ok(Cyclic::CCA));
+		assert_eq!(
+			Cyclic::parse("R1/T3/C1"),
+			Err(ParseRuleError::InvalidStateCount)
+		);
+		assert_eq!(Cyclic::SPIRALS.to_string(), "R3/T5/C8/NM");
+	}
+
+	#[test]
+	fn cells_advance_when_enough_neighbors_hold_the_next_color() {
+		let rule = Cyclic::new(3, 2, 1, Neighborhood::VonNeumann);
+		let board = Board {
+			cells: [0, 1, 0, 1, 0, 0, 0, 0, 2]
+				.into_iter()
+				.map(|color| Cell {
+					state: color << 1,
+					..Default::default()
+				})
+				.collect(),
+			width: 3,
+			height: 3,
+			generation: 0,
+			boundary: Boundary::Dead,
+		};
+
+		// The center has two von Neumann neighbors with color 1
+		assert_eq!(
+			rule.next_state(&board, 1, 1, Visibility::ALL),
+			1 << 1 | 0b01
+		);
+		// The bottom right corner's next color, 0, is all around it
+		assert_eq!(rule.next_state(&board, 2, 2, Visibility::ALL), 0b01);
+		// The top right corner only has one neighbor with color 1
+		assert_eq!(rule.next_state(&board, 0, 2, Visibility::ALL), 0);
+	}
+}
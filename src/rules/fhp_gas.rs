@@ -0,0 +1,266 @@
+use crate::rules::{Rule, Visibility, Vitality};
+use crate::world::Board;
+use nannou::color::LinSrgba;
+use nannou::rand;
+use std::fmt::Write;
+
+/// FHP lattice gas. Unlike the square `LatticeGas`, particles travel in six
+/// directions on a hexagonal lattice, which makes the flow look isotropic.
+///
+/// The hex lattice is laid over the board by shifting odd rows half a cell to
+/// the right. Each direction is a bit, with the lowest bit set whenever the
+/// cell holds any particles.
+#[derive(Debug)]
+pub struct FhpGas;
+impl FhpGas {
+	fn random_dir() -> u8 {
+		dir_bit(rand::random_range(0, 6))
+	}
+}
+
+impl Rule for FhpGas {
+	fn on(&self) -> u8 {
+		FhpGas::random_dir() | POPULATED
+	}
+
+	fn off(&self) -> u8 {
+		0
+	}
+
+	fn random(&self) -> u8 {
+		let mut state = 0;
+		for dir in 0..6 {
+			if rand::random_range(0, 5) == 0 {
+				state |= dir_bit(dir);
+			}
+		}
+		if state != 0 {
+			state |= POPULATED;
+		}
+		state
+	}
+
+	/// A particle moving any direction is alive
+	fn export(&self, state: u8) -> Vitality {
+		if state != 0 {
+			Vitality::Alive
+		} else {
+			Vitality::Dead
+		}
+	}
+
+	/// Live cells become a particle with a fixed heading, so that growth
+	/// stays deterministic
+	fn import(&self, vitality: Vitality) -> u8 {
+		match vitality {
+			Vitality::Alive => dir_bit(EAST) | POPULATED,
+			_ => 0,
+		}
+	}
+
+	/// Collisions turn left or right depending on the generation
+	fn uses_generation(&self) -> bool {
+		true
+	}
+
+	fn color(&self, state: u8) -> LinSrgba {
+		let density = (state & DIRECTIONS).count_ones() as f32 / 6.0;
+		let density = density.sqrt();
+		LinSrgba::new(0.8 * density, 0.85 * density, density, 1.0)
+	}
+
+	fn rule_color(&self) -> LinSrgba {
+		LinSrgba::new(0.8, 0.85, 1.0, 0.125)
+	}
+
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		next_cell_state(board, row, col, visibility)
+	}
+
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
+		write!(output, "{:07b}", state)
+	}
+}
+
+const POPULATED: u8 = 0b0000001;
+const DIRECTIONS: u8 = 0b1111110;
+
+// Directions, counterclockwise from east
+const EAST: usize = 0;
+const NORTHEAST: usize = 1;
+const NORTHWEST: usize = 2;
+const WEST: usize = 3;
+const SOUTHWEST: usize = 4;
+const SOUTHEAST: usize = 5;
+
+const fn dir_bit(dir: usize) -> u8 {
+	1 << (dir + 1)
+}
+
+fn opposite(dir: usize) -> usize {
+	(dir + 3) % 6
+}
+
+/// Finds the neighbor of a cell in a direction, or `None` off the edge of the
+/// board. Only a torus wraps around, which needs an even number of rows to
+/// keep the shifted rows lined up.
+fn neighbor(board: &Board, row: usize, col: usize, dir: usize) -> Option<(usize, usize)> {
+	let odd_row = row % 2 == 1;
+	let (dr, dc) = match dir {
+		EAST => (0, 1),
+		WEST => (0, -1),
+		NORTHEAST | NORTHWEST | SOUTHWEST | SOUTHEAST => {
+			let dr = if dir == NORTHEAST || dir == NORTHWEST {
+				-1
+			} else {
+				1
+			};
+			let dc = match (dir == NORTHEAST || dir == SOUTHEAST, odd_row) {
+				(true, true) => 1,
+				(false, false) => -1,
+				_ => 0,
+			};
+			(dr, dc)
+		}
+		_ => unreachable!(),
+	};
+	board
+		.wrapping_neighbor_idx(row, col, dr, dc)
+		.map(|idx| (idx / board.width, idx % board.width))
+}
+
+fn rotate(dirs: u8, steps: usize) -> u8 {
+	let dirs = dirs >> 1;
+	let rotated = (dirs << steps | dirs >> (6 - steps)) & 0b111111;
+	rotated << 1
+}
+
+fn collide(dirs: u8, chirality: bool) -> u8 {
+	const THREE_BODY_A: u8 = dir_bit(EAST) | dir_bit(NORTHWEST) | dir_bit(SOUTHWEST);
+	const THREE_BODY_B: u8 = dir_bit(NORTHEAST) | dir_bit(WEST) | dir_bit(SOUTHEAST);
+
+	if dirs == THREE_BODY_A {
+		return THREE_BODY_B;
+	} else if dirs == THREE_BODY_B {
+		return THREE_BODY_A;
+	}
+
+	// Two particles colliding head on scatter 60 degrees one way or the other
+	for dir in EAST..WEST {
+		if dirs == dir_bit(dir) | dir_bit(opposite(dir)) {
+			return if chirality {
+				rotate(dirs, 1)
+			} else {
+				rotate(dirs, 5)
+			};
+		}
+	}
+
+	dirs
+}
+
+fn next_cell_state(board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+	let state = board[row * board.width + col].state;
+	let mut combined_states = 0;
+	for dir in EAST..=SOUTHEAST {
+		// A particle arrives moving in `dir` from the neighbor behind it, as
+		// long as the rule can see the neighbor's ruleset
+		if let Some((from_row, from_col)) = neighbor(board, row, col, opposite(dir)) {
+			let from = board[from_row * board.width + from_col];
+			if visibility.sees(from.ruleset) {
+				combined_states |= from.state & dir_bit(dir);
+			}
+		}
+
+		// A particle about to leave the board bounces off the edge and stays
+		// put, turned around, unless the boundary lets it go. Nothing can
+		// arrive from past the edge, so it never runs into another particle.
+		if state & dir_bit(dir) > 0
+			&& board.boundary.reflects_particles()
+			&& neighbor(board, row, col, dir).is_none()
+		{
+			combined_states |= dir_bit(opposite(dir));
+		}
+	}
+
+	if combined_states == 0 {
+		return combined_states;
+	}
+
+	// Alternate which way two body collisions turn, in space and time, so the
+	// gas doesn't drift one way without having to roll dice.
+	let chirality = (board.generation + row as u64 + col as u64) & 1 == 0;
+	combined_states = collide(combined_states, chirality);
+
+	combined_states | POPULATED
+}
+
+#[cfg(test)]
+mod test {
+	use super::{collide, dir_bit, neighbor, next_cell_state, EAST, NORTHEAST, SOUTHWEST, WEST};
+	use crate::rules::Visibility;
+	use crate::world::{mix, Board, Boundary, Cell};
+
+	#[test]
+	fn odd_rows_are_shifted_right() {
+		let board = Board {
+			cells: vec![Default::default(); 9],
+			width: 3,
+			height: 3,
+			generation: 0,
+			boundary: Boundary::Dead,
+		};
+		assert_eq!(neighbor(&board, 1, 1, NORTHEAST), Some((0, 2)));
+		assert_eq!(neighbor(&board, 2, 1, NORTHEAST), Some((1, 1)));
+		assert_eq!(neighbor(&board, 2, 0, SOUTHWEST), None);
+		assert_eq!(neighbor(&board, 1, 0, SOUTHWEST), Some((2, 0)));
+	}
+
+	#[test]
+	fn collisions_conserve_particles() {
+		let head_on = dir_bit(EAST) | dir_bit(WEST);
+		for dirs in (0..64u8).map(|dirs| dirs << 1) {
+			for chirality in [false, true] {
+				assert_eq!(collide(dirs, chirality).count_ones(), dirs.count_ones());
+			}
+		}
+		assert_ne!(collide(head_on, true), head_on);
+		assert_ne!(collide(head_on, true), collide(head_on, false));
+	}
+
+	#[test]
+	fn walls_conserve_particles() {
+		let (width, height) = (7, 6);
+		let mut board = Board {
+			cells: (0..width * height)
+				.map(|idx| Cell {
+					state: (mix(2, 0, idx / width, idx % width) % 128) as u8 | 0b1,
+					..Default::default()
+				})
+				.collect(),
+			width,
+			height,
+			generation: 0,
+			boundary: Boundary::Dead,
+		};
+		let particles = |board: &Board| {
+			board
+				.iter()
+				.map(|cell| (cell.state >> 1).count_ones())
+				.sum::<u32>()
+		};
+
+		let start = particles(&board);
+		for _ in 0..20 {
+			let cells = (0..width * height)
+				.map(|idx| Cell {
+					state: next_cell_state(&board, idx / width, idx % width, Visibility::ALL),
+					..board[idx]
+				})
+				.collect();
+			board.cells = cells;
+			board.generation += 1;
+			assert_eq!(particles(&board), start);
+		}
+	}
+}
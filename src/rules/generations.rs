@@ -0,0 +1,211 @@
+use crate::rules::life_like::count_live_neighbors;
+use crate::rules::{LifeLike, ParseRuleError, Rule, Visibility, Vitality};
+use crate::world::Board;
+use nannou::color::LinSrgba;
+use nannou::rand;
+use std::fmt::Write;
+
+/// A "Generations" rule: a Life-like rule where a cell that fails to survive
+/// doesn't die right away, but passes through `states - 2` dying states
+/// before it can be born again. Only live cells count as neighbors.
+///
+/// Live cells are `0b01`, and dying states are stored in the upper bits
+/// (`2`, `4`, `6`...) so that other rules never mistake them for live cells.
+#[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct Generations {
+	rule: LifeLike,
+	states: u8,
+}
+
+impl Generations {
+	pub const MAX_STATES: u8 = 129;
+
+	pub const STAR_WARS: Generations = Generations::new(&[2], &[3, 4, 5], 4);
+	pub const FROGS: Generations = Generations::new(&[3, 4], &[1, 2], 3);
+	pub const STICKS: Generations = Generations::new(&[2], &[3, 4, 5, 6], 6);
+
+	pub const fn new(birth: &[u8], survival: &[u8], states: u8) -> Generations {
+		Generations {
+			rule: LifeLike::new(birth, survival),
+			states,
+		}
+	}
+
+	/// Parses a rule in B/S/C notation, like `B2/S/3` or `B2/S345/4`. The
+	/// state count comes last and may be prefixed with `C` or `G`.
+	pub fn parse(rule: &str) -> Result<Generations, ParseRuleError> {
+		let (life_like, states) = rule
+			.trim()
+			.rsplit_once('/')
+			.ok_or(ParseRuleError::MissingStateCount)?;
+		let states = states.trim();
+		let states = states
+			.strip_prefix(['C', 'c', 'G', 'g'])
+			.unwrap_or(states)
+			.parse::<u8>()
+			.map_err(|_| ParseRuleError::InvalidStateCount)?;
+		if !(2..=Generations::MAX_STATES).contains(&states) {
+			return Err(ParseRuleError::InvalidStateCount);
+		}
+
+		Ok(Generations {
+			rule: LifeLike::parse(life_like)?,
+			states,
+		})
+	}
+
+	/// Converts a state from 0 to `states - 1` (dead, alive, then each
+	/// dying state in order) into its bit representation.
+	pub(super) fn encode(generation: u8) -> u8 {
+		match generation {
+			0 => DEAD,
+			1 => ALIVE,
+			dying => (dying - 1) << 1,
+		}
+	}
+
+	pub(super) fn decode(state: u8) -> u8 {
+		match state {
+			DEAD => 0,
+			ALIVE => 1,
+			dying => (dying >> 1) + 1,
+		}
+	}
+
+	pub fn next_state(self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		let idx = row * board.width + col;
+		match Generations::decode(board[idx].state) {
+			0 => {
+				let live_neighbors = count_live_neighbors(board, row, col, visibility);
+				if self.rule.is_born(live_neighbors) {
+					ALIVE
+				} else {
+					DEAD
+				}
+			}
+			1 => {
+				let live_neighbors = count_live_neighbors(board, row, col, visibility);
+				if self.rule.survives(live_neighbors) {
+					ALIVE
+				} else {
+					Generations::encode(2 % self.states)
+				}
+			}
+			dying => Generations::encode((dying + 1) % self.states),
+		}
+	}
+}
+
+impl Rule for Generations {
+	fn on(&self) -> u8 {
+		ALIVE
+	}
+
+	fn off(&self) -> u8 {
+		DEAD
+	}
+
+	fn random(&self) -> u8 {
+		Generations::encode(rand::random_range(0, self.states))
+	}
+
+	/// History becomes the first dying state, if the rule has any
+	fn import(&self, vitality: Vitality) -> u8 {
+		match vitality {
+			Vitality::Alive => ALIVE,
+			Vitality::WasAlive if self.states > 2 => Generations::encode(2),
+			_ => DEAD,
+		}
+	}
+
+	fn color(&self, state: u8) -> LinSrgba {
+		match Generations::decode(state) {
+			0 => LinSrgba::new(0.0, 0.0, 0.0, 1.0),
+			1 => LinSrgba::new(1.0, 0.9, 0.4, 1.0),
+			dying => {
+				// Fade from bright to dark over the dying states
+				let fade = 1.0 - (dying - 1) as f32 / (self.states - 1) as f32;
+				LinSrgba::new(0.8 * fade, 0.2 * fade, 0.9 * fade, 1.0)
+			}
+		}
+	}
+
+	fn rule_color(&self) -> LinSrgba {
+		LinSrgba::new(0.8, 0.2, 0.9, 0.125)
+	}
+
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		self.next_state(board, row, col, visibility)
+	}
+
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
+		write!(output, "{}", Generations::decode(state))
+	}
+}
+
+impl std::fmt::Display for Generations {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(fmt, "{}/{}", self.rule, self.states)
+	}
+}
+
+impl std::fmt::Debug for Generations {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		std::fmt::Display::fmt(self, fmt)
+	}
+}
+
+impl std::str::FromStr for Generations {
+	type Err = ParseRuleError;
+	fn from_str(rule: &str) -> Result<Self, Self::Err> {
+		Generations::parse(rule)
+	}
+}
+
+const DEAD: u8 = 0;
+const ALIVE: u8 = 0b01;
+
+#[cfg(test)]
+mod test {
+	use super::{Generations, ParseRuleError};
+	use crate::rules::{Rule, Ruleset, Visibility};
+	use crate::world::{Board, Boundary, Cell};
+
+	#[test]
+	fn parse_reads_state_count() {
+		assert_eq!(Generations::parse("B2/S345/4"), Ok(Generations::STAR_WARS));
+		assert_eq!(Generations::parse("B34/S12/C3"), Ok(Generations::FROGS));
+		assert_eq!(
+			Generations::parse("B2/S"),
+			Err(ParseRuleError::InvalidStateCount)
+		);
+		assert_eq!(
+			Generations::parse("B2/S/1"),
+			Err(ParseRuleError::InvalidStateCount)
+		);
+		assert_eq!(Generations::STICKS.to_string(), "B2/S3456/6");
+	}
+
+	#[test]
+	fn dying_cells_count_down_and_are_never_live() {
+		let rule = Generations::STAR_WARS;
+		let mut board = Board {
+			cells: vec![Cell {
+				ruleset: Ruleset::default(),
+				state: rule.on(),
+			}],
+			width: 1,
+			height: 1,
+			generation: 0,
+			boundary: Boundary::Dead,
+		};
+
+		let mut states = vec![];
+		for _ in 0..4 {
+			board[0].state = rule.next_cell_state(&board, 0, 0, Visibility::ALL);
+			states.push(board[0].state);
+		}
+
+		assert_eq!(states, vec![0b010, 0b100, 0, 0]);
+	}
+}
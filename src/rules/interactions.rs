@@ -0,0 +1,136 @@
+use crate::rules::Ruleset;
+use crate::world::{Board, Cell};
+
+/// How much the live cells of each ruleset count as live neighbors to the
+/// rules of every other ruleset. A weight of 1 is a normal neighbor, 0 makes
+/// a ruleset invisible, and negative weights make it hostile, taking away
+/// from the count.
+///
+/// Every weight is 1 until one is set, which is how rules have always seen
+/// each other.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Interactions {
+	rulesets: usize,
+	weights: Vec<i8>,
+	/// Whether any weight isn't 1, worked out whenever one is set
+	customized: bool,
+}
+
+impl Interactions {
+	/// Every ruleset only sees its own cells
+	pub fn isolated(rulesets: usize) -> Interactions {
+		let mut weights = vec![0; rulesets * rulesets];
+		for idx in 0..rulesets {
+			weights[idx * rulesets + idx] = 1;
+		}
+		Interactions {
+			rulesets,
+			weights,
+			customized: rulesets > 1,
+		}
+	}
+
+	pub fn is_uniform(&self) -> bool {
+		!self.customized
+	}
+
+	/// How much a live cell of `neighbor` counts for a cell of `observer`
+	pub fn get(&self, observer: Ruleset, neighbor: Ruleset) -> i8 {
+		self.seen_by(observer).weight(neighbor)
+	}
+
+	pub fn set(&mut self, observer: Ruleset, neighbor: Ruleset, weight: i8) {
+		let needed = observer.index().max(neighbor.index()) + 1;
+		if needed > self.rulesets {
+			let mut weights = vec![1; needed * needed];
+			for row in 0..self.rulesets {
+				weights[row * needed..row * needed + self.rulesets]
+					.copy_from_slice(&self.weights[row * self.rulesets..(row + 1) * self.rulesets]);
+			}
+			self.rulesets = needed;
+			self.weights = weights;
+		}
+		self.weights[observer.index() * self.rulesets + neighbor.index()] = weight;
+		self.customized = self.weights.iter().any(|&weight| weight != 1);
+	}
+
+	pub fn seen_by(&self, observer: Ruleset) -> Visibility<'_> {
+		if observer.index() >= self.rulesets || self.is_uniform() {
+			return Visibility::ALL;
+		}
+		let start = observer.index() * self.rulesets;
+		Visibility {
+			weights: &self.weights[start..start + self.rulesets],
+		}
+	}
+}
+
+/// One row of the `Interactions`: how the cells of every ruleset look to the
+/// rule computing the next state of a cell.
+#[derive(Copy, Clone, Debug)]
+pub struct Visibility<'a> {
+	weights: &'a [i8],
+}
+
+impl Visibility<'static> {
+	/// Every live cell counts as a live neighbor
+	pub const ALL: Visibility<'static> = Visibility { weights: &[] };
+}
+
+impl<'a> Visibility<'a> {
+	/// Whether every ruleset counts normally, so rules can take their fast
+	/// paths
+	pub fn is_uniform(&self) -> bool {
+		self.weights.is_empty()
+	}
+
+	pub fn weight(&self, ruleset: Ruleset) -> i8 {
+		self.weights.get(ruleset.index()).copied().unwrap_or(1)
+	}
+
+	pub fn sees(&self, ruleset: Ruleset) -> bool {
+		self.weight(ruleset) > 0
+	}
+
+	/// How much a cell counts toward a count of live neighbors
+	pub fn live(&self, cell: Cell) -> i32 {
+		(cell.state & 0b01) as i32 * self.weight(cell.ruleset) as i32
+	}
+
+	/// How much the cell `dr` rows and `dc` columns away counts toward a
+	/// count of live neighbors. Cells past the edge of a live boundary don't
+	/// belong to any ruleset, and always count normally.
+	pub fn neighbor(&self, board: &Board, row: usize, col: usize, dr: isize, dc: isize) -> i32 {
+		match board.neighbor_idx(row, col, dr, dc) {
+			Some(idx) => self.live(board[idx]),
+			None => (board.neighbor_state(row, col, dr, dc) & 0b01) as i32,
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::Interactions;
+	use crate::rules::Ruleset;
+
+	#[test]
+	fn weights_default_to_one() {
+		let mut interactions = Interactions::default();
+		assert!(interactions.is_uniform());
+		assert!(interactions.seen_by(Ruleset::SEEDS).is_uniform());
+
+		interactions.set(Ruleset::LIFE, Ruleset::SEEDS, -1);
+		assert_eq!(interactions.get(Ruleset::LIFE, Ruleset::SEEDS), -1);
+		assert_eq!(interactions.get(Ruleset::SEEDS, Ruleset::LIFE), 1);
+		assert_eq!(interactions.get(Ruleset::LIFE, Ruleset::ANNEAL), 1);
+
+		// Growing the matrix keeps the weights that were already set
+		interactions.set(Ruleset::ANNEAL, Ruleset::LIFE, 0);
+		assert_eq!(interactions.get(Ruleset::LIFE, Ruleset::SEEDS), -1);
+		assert!(!interactions.seen_by(Ruleset::ANNEAL).sees(Ruleset::LIFE));
+
+		let isolated = Interactions::isolated(3);
+		assert_eq!(isolated.get(Ruleset::ANTI_LIFE, Ruleset::ANTI_LIFE), 1);
+		assert_eq!(isolated.get(Ruleset::ANTI_LIFE, Ruleset::LIFE), 0);
+	}
+}
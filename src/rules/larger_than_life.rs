@@ -0,0 +1,368 @@
+use crate::rules::{Generations, ParseRuleError, Rule, Visibility};
+use crate::world::Board;
+use nannou::color::LinSrgba;
+use nannou::rand;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+/// Larger than Life: a Life-like rule that counts live cells over a large
+/// neighborhood, with ranges of counts for birth and survival instead of
+/// individual counts.
+///
+/// With fewer than 3 states, cells keep the `0b10` "was alive" bit like
+/// `LifeLike`. With more, dying cells count down like `Generations`.
+#[derive(Clone, Eq, PartialEq)]
+pub struct LargerThanLife {
+	radius: u8,
+	states: u8,
+	include_center: bool,
+	neighborhood: Neighborhood,
+	survival: RangeInclusive<u16>,
+	birth: RangeInclusive<u16>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Neighborhood {
+	Moore,
+	VonNeumann,
+}
+
+impl LargerThanLife {
+	pub const MAX_RADIUS: u8 = 10;
+
+	pub const BOSCO: LargerThanLife = LargerThanLife {
+		radius: 5,
+		states: 0,
+		include_center: true,
+		neighborhood: Neighborhood::Moore,
+		survival: 34..=58,
+		birth: 34..=45,
+	};
+
+	pub const MAJORITY: LargerThanLife = LargerThanLife {
+		radius: 4,
+		states: 0,
+		include_center: true,
+		neighborhood: Neighborhood::Moore,
+		survival: 41..=81,
+		birth: 41..=81,
+	};
+
+	/// Parses a rule in Golly's notation, like
+	/// `R5,C0,M1,S34..58,B34..45,NM`. `C`, `M` and `N` are optional and
+	/// default to 2 states, excluding the center cell, in a Moore
+	/// neighborhood.
+	pub fn parse(rule: &str) -> Result<LargerThanLife, ParseRuleError> {
+		let mut radius = None;
+		let mut states = 0;
+		let mut include_center = false;
+		let mut neighborhood = Neighborhood::Moore;
+		let mut survival = None;
+		let mut birth = None;
+
+		for part in rule.trim().split(',') {
+			let part = part.trim();
+			let value = part.get(1..).unwrap_or_default();
+			match part.chars().next() {
+				Some('R' | 'r') => {
+					radius = Some(
+						value
+							.parse::<u8>()
+							.ok()
+							.filter(|radius| (1..=LargerThanLife::MAX_RADIUS).contains(radius))
+							.ok_or(ParseRuleError::InvalidSegment('R'))?,
+					)
+				}
+				Some('C' | 'c') => {
+					states = value
+						.parse::<u8>()
+						.ok()
+						.filter(|&states| states <= Generations::MAX_STATES)
+						.ok_or(ParseRuleError::InvalidStateCount)?
+				}
+				Some('M' | 'm') => {
+					include_center = match value {
+						"0" => false,
+						"1" => true,
+						_ => return Err(ParseRuleError::InvalidSegment('M')),
+					}
+				}
+				Some('S' | 's') => survival = Some(parse_range(value)?),
+				Some('B' | 'b') => birth = Some(parse_range(value)?),
+				Some('N' | 'n') => {
+					neighborhood = match value {
+						"M" | "m" => Neighborhood::Moore,
+						"N" | "n" => Neighborhood::VonNeumann,
+						_ => return Err(ParseRuleError::InvalidSegment('N')),
+					}
+				}
+				Some(c) => return Err(ParseRuleError::UnexpectedCharacter(c)),
+				None => return Err(ParseRuleError::Empty),
+			}
+		}
+
+		Ok(LargerThanLife {
+			radius: radius.ok_or(ParseRuleError::InvalidSegment('R'))?,
+			states,
+			include_center,
+			neighborhood,
+			survival: survival.ok_or(ParseRuleError::MissingSurvival)?,
+			birth: birth.ok_or(ParseRuleError::MissingBirth)?,
+		})
+	}
+
+	fn has_dying_states(&self) -> bool {
+		self.states > 2
+	}
+
+	/// Counts live cells in the neighborhood, one contiguous run of cells per
+	/// row. Neighborhoods that reach past the edges of the board, or that see
+	/// some rulesets differently, are counted one cell at a time instead.
+	fn count_live_neighbors(
+		&self,
+		board: &Board,
+		row: usize,
+		col: usize,
+		visibility: Visibility,
+	) -> u16 {
+		let radius = self.radius as usize;
+		let fits_on_board = row >= radius
+			&& col >= radius
+			&& row + radius < board.height
+			&& col + radius < board.width;
+
+		let mut live = 0;
+		if fits_on_board && visibility.is_uniform() {
+			for y in row - radius..=row + radius {
+				let reach = self.reach(radius, row.abs_diff(y));
+				let row_start = y * board.width;
+				live += board.cells[row_start + col - reach..=row_start + col + reach]
+					.iter()
+					.map(|cell| (cell.state & 0b01) as i32)
+					.sum::<i32>();
+			}
+		} else {
+			let radius = radius as isize;
+			for dr in -radius..=radius {
+				let reach = self.reach(radius as usize, dr.unsigned_abs()) as isize;
+				for dc in -reach..=reach {
+					live += visibility.neighbor(board, row, col, dr, dc);
+				}
+			}
+		}
+
+		if !self.include_center {
+			live -= visibility.live(board[row * board.width + col]);
+		}
+
+		live.max(0) as u16
+	}
+
+	/// How far the neighborhood reaches to either side, `distance` rows away
+	/// from the center
+	fn reach(&self, radius: usize, distance: usize) -> usize {
+		match self.neighborhood {
+			Neighborhood::Moore => radius,
+			Neighborhood::VonNeumann => radius - distance,
+		}
+	}
+
+	pub fn next_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		let state = board[row * board.width + col].state;
+
+		if self.has_dying_states() {
+			return match Generations::decode(state) {
+				0 => {
+					let live_neighbors = self.count_live_neighbors(board, row, col, visibility);
+					Generations::encode(self.birth.contains(&live_neighbors) as u8)
+				}
+				1 => {
+					let live_neighbors = self.count_live_neighbors(board, row, col, visibility);
+					if self.survival.contains(&live_neighbors) {
+						Generations::encode(1)
+					} else {
+						Generations::encode(2)
+					}
+				}
+				dying => Generations::encode((dying + 1) % self.states),
+			};
+		}
+
+		let live_neighbors = self.count_live_neighbors(board, row, col, visibility);
+		if state & 0b01 > 0 {
+			self.survival.contains(&live_neighbors) as u8 | 0b10
+		} else {
+			self.birth.contains(&live_neighbors) as u8
+		}
+	}
+}
+
+impl Rule for LargerThanLife {
+	fn on(&self) -> u8 {
+		0b01
+	}
+
+	fn off(&self) -> u8 {
+		0b00
+	}
+
+	fn random(&self) -> u8 {
+		rand::random_range(0, 2)
+	}
+
+	fn radius(&self) -> usize {
+		self.radius as usize
+	}
+
+	fn color(&self, state: u8) -> LinSrgba {
+		if self.has_dying_states() {
+			return match Generations::decode(state) {
+				0 => LinSrgba::new(0.0, 0.0, 0.0, 1.0),
+				1 => LinSrgba::new(0.3, 0.9, 1.0, 1.0),
+				dying => {
+					let fade = 1.0 - (dying - 1) as f32 / (self.states - 1) as f32;
+					LinSrgba::new(0.1 * fade, 0.3 * fade, 0.8 * fade, 1.0)
+				}
+			};
+		}
+
+		match state {
+			0b11 => LinSrgba::new(0.3, 0.9, 1.0, 1.0),
+			0b01 => LinSrgba::new(0.6, 1.0, 1.0, 1.0),
+			0b10 => LinSrgba::new(0.0, 0.1, 0.3, 1.0),
+			_ => LinSrgba::new(0.0, 0.0, 0.0, 1.0),
+		}
+	}
+
+	fn rule_color(&self) -> LinSrgba {
+		LinSrgba::new(0.3, 0.9, 1.0, 0.125)
+	}
+
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		self.next_state(board, row, col, visibility)
+	}
+
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
+		if self.has_dying_states() {
+			write!(output, "{}", Generations::decode(state))
+		} else {
+			write!(output, "{:02b}", state)
+		}
+	}
+}
+
+impl std::fmt::Display for LargerThanLife {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(
+			fmt,
+			"R{},C{},M{},S{}..{},B{}..{},N{}",
+			self.radius,
+			self.states,
+			self.include_center as u8,
+			self.survival.start(),
+			self.survival.end(),
+			self.birth.start(),
+			self.birth.end(),
+			match self.neighborhood {
+				Neighborhood::Moore => "M",
+				Neighborhood::VonNeumann => "N",
+			}
+		)
+	}
+}
+
+impl std::fmt::Debug for LargerThanLife {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		std::fmt::Display::fmt(self, fmt)
+	}
+}
+
+impl std::str::FromStr for LargerThanLife {
+	type Err = ParseRuleError;
+	fn from_str(rule: &str) -> Result<Self, Self::Err> {
+		LargerThanLife::parse(rule)
+	}
+}
+
+fn parse_range(range: &str) -> Result<RangeInclusive<u16>, ParseRuleError> {
+	let (start, end) = range
+		.split_once("..")
+		.or_else(|| range.split_once('-'))
+		.unwrap_or((range, range));
+	match (start.trim().parse(), end.trim().parse()) {
+		(Ok(start), Ok(end)) if start <= end => Ok(start..=end),
+		_ => Err(ParseRuleError::InvalidRange),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{LargerThanLife, Neighborhood};
+	use crate::rules::{ParseRuleError, Visibility};
+	use crate::world::{Board, Boundary, Cell};
+
+	#[test]
+	fn parse_reads_golly_notation() {
+		assert_eq!(
+			LargerThanLife::parse("R5,C0,M1,S34..58,B34..45,NM"),
+			Ok(LargerThanLife::BOSCO)
+		);
+
+		let rule = LargerThanLife::parse("R2,S3-4,B3,NN").unwrap();
+		assert_eq!(rule.neighborhood, Neighborhood::VonNeumann);
+		assert_eq!(rule.to_string(), "R2,C0,M0,S3..4,B3..3,NN");
+
+		assert_eq!(
+			LargerThanLife::parse("R0,S3,B3"),
+			Err(ParseRuleError::InvalidSegment('R'))
+		);
+		assert_eq!(
+			LargerThanLife::parse("R1,S4..3,B3"),
+			Err(ParseRuleError::InvalidRange)
+		);
+	}
+
+	#[test]
+	fn neighborhood_follows_the_boundary_at_board_edges() {
+		let mut board = Board {
+			cells: vec![
+				Cell {
+					state: 0b01,
+					..Default::default()
+				};
+				25
+			],
+			width: 5,
+			height: 5,
+			generation: 0,
+			boundary: Boundary::Dead,
+		};
+
+		let moore = LargerThanLife::parse("R2,M1,S0,B0").unwrap();
+		assert_eq!(
+			moore.count_live_neighbors(&board, 2, 2, Visibility::ALL),
+			25
+		);
+		assert_eq!(moore.count_live_neighbors(&board, 0, 0, Visibility::ALL), 9);
+
+		let von_neumann = LargerThanLife::parse("R2,M0,S0,B0,NN").unwrap();
+		assert_eq!(
+			von_neumann.count_live_neighbors(&board, 2, 2, Visibility::ALL),
+			12
+		);
+		assert_eq!(
+			von_neumann.count_live_neighbors(&board, 0, 0, Visibility::ALL),
+			5
+		);
+
+		board.boundary = Boundary::Torus;
+		assert_eq!(
+			moore.count_live_neighbors(&board, 0, 0, Visibility::ALL),
+			25
+		);
+		assert_eq!(
+			von_neumann.count_live_neighbors(&board, 0, 0, Visibility::ALL),
+			12
+		);
+	}
+}
@@ -1,71 +1,85 @@
-use crate::rules::Ruleset;
+use crate::rules::{Rule, Visibility, Vitality};
 use crate::world::{Board, Cell};
 use nannou::color::LinSrgba;
 use nannou::rand;
 use std::fmt::Write;
 
+#[derive(Debug)]
 pub struct LatticeGas;
 impl LatticeGas {
-	pub fn empty() -> Cell {
-		Cell {
-			ruleset: Ruleset::LatticeGas,
-			state: 0,
-		}
+	fn random_dir() -> u8 {
+		1u8 << rand::random_range::<u8>(1, 5)
 	}
 
-	pub fn random() -> Cell {
-		let state = if rand::random::<bool>() {
-			LatticeGas::random_dir() | POPULATED
-		} else {
-			0
-		};
+	fn going_up(cell: Cell) -> u8 {
+		cell.state & GOING_UP
+	}
 
-		Cell {
-			ruleset: Ruleset::LatticeGas,
-			state,
-		}
+	fn going_down(cell: Cell) -> u8 {
+		cell.state & GOING_DOWN
 	}
 
-	pub fn random_populated() -> Cell {
-		Cell {
-			ruleset: Ruleset::LatticeGas,
-			state: LatticeGas::random_dir() | POPULATED,
-		}
+	fn going_left(cell: Cell) -> u8 {
+		cell.state & GOING_LEFT
 	}
 
-	fn random_dir() -> u8 {
-		1u8 << rand::random_range::<u8>(1, 5)
+	fn going_right(cell: Cell) -> u8 {
+		cell.state & GOING_RIGHT
 	}
+}
 
-	pub fn next_cell_state(board: &Board, row: usize, col: usize) -> Cell {
-		next_cell_state(board, row, col)
+impl Rule for LatticeGas {
+	fn on(&self) -> u8 {
+		LatticeGas::random_dir() | POPULATED
 	}
 
-	pub fn color(cell: Cell) -> LinSrgba {
-		if cell.state & POPULATED > 0 {
-			LinSrgba::new(0.0, 0.0, 0.0, 1.0)
+	fn off(&self) -> u8 {
+		0
+	}
+
+	fn random(&self) -> u8 {
+		if rand::random::<bool>() {
+			LatticeGas::random_dir() | POPULATED
 		} else {
-			LinSrgba::new(1.0, 1.0, 1.0, 1.0)
+			0
 		}
 	}
 
-	fn going_up(cell: Cell) -> u8 {
-		cell.state & GOING_UP
+	/// A particle moving any direction is alive
+	fn export(&self, state: u8) -> Vitality {
+		if state != 0 {
+			Vitality::Alive
+		} else {
+			Vitality::Dead
+		}
 	}
 
-	fn going_down(cell: Cell) -> u8 {
-		cell.state & GOING_DOWN
+	/// Live cells become a particle with a fixed heading, so that growth
+	/// stays deterministic
+	fn import(&self, vitality: Vitality) -> u8 {
+		match vitality {
+			Vitality::Alive => GOING_RIGHT | POPULATED,
+			_ => 0,
+		}
 	}
 
-	fn going_left(cell: Cell) -> u8 {
-		cell.state & GOING_LEFT
+	fn color(&self, state: u8) -> LinSrgba {
+		if state & POPULATED > 0 {
+			LinSrgba::new(0.0, 0.0, 0.0, 1.0)
+		} else {
+			LinSrgba::new(1.0, 1.0, 1.0, 1.0)
+		}
 	}
 
-	fn going_right(cell: Cell) -> u8 {
-		cell.state & GOING_RIGHT
+	fn rule_color(&self) -> LinSrgba {
+		LinSrgba::new(1.0, 1.0, 1.0, 0.125)
+	}
+
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		next_cell_state(board, row, col, visibility)
 	}
 
-	pub fn write_debug<W: Write>(output: &mut W, state: u8) -> std::fmt::Result {
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
 		write!(output, "{:04b}", state)
 	}
 }
@@ -76,26 +90,22 @@ const GOING_LEFT: u8 = 0b00100;
 const GOING_RIGHT: u8 = 0b00010;
 const POPULATED: u8 = 0b00001;
 
-fn next_cell_state(board: &Board, row: usize, col: usize) -> Cell {
-	let mut combined_states = 0;
-	if row > 0 {
-		combined_states |= LatticeGas::going_down(board[(row - 1) * board.width + col]);
-	}
-	if row < board.height - 1 {
-		combined_states |= LatticeGas::going_up(board[(row + 1) * board.width + col]);
-	}
-	if col > 0 {
-		combined_states |= LatticeGas::going_right(board[row * board.width + col - 1]);
-	}
-	if col < board.width - 1 {
-		combined_states |= LatticeGas::going_left(board[row * board.width + col + 1]);
-	}
+/// Particles only arrive from cells of rulesets the rule can see
+fn next_cell_state(board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+	let arriving = |dr, dc, going: fn(Cell) -> u8| {
+		board
+			.wrapping_neighbor_idx(row, col, dr, dc)
+			.filter(|&idx| visibility.sees(board[idx].ruleset))
+			.map_or(0, |idx| going(board[idx]))
+	};
+
+	let mut combined_states = arriving(-1, 0, LatticeGas::going_down)
+		| arriving(1, 0, LatticeGas::going_up)
+		| arriving(0, -1, LatticeGas::going_right)
+		| arriving(0, 1, LatticeGas::going_left);
 
 	if combined_states == 0 {
-		return Cell {
-			ruleset: Ruleset::LatticeGas,
-			state: combined_states,
-		};
+		return combined_states;
 	}
 
 	if combined_states == GOING_UP | GOING_DOWN {
@@ -104,23 +114,22 @@ fn next_cell_state(board: &Board, row: usize, col: usize) -> Cell {
 		combined_states = GOING_UP | GOING_DOWN;
 	}
 
-	if col == 0 && combined_states & GOING_LEFT > 0 {
-		combined_states = combined_states & (!GOING_LEFT) | GOING_RIGHT;
-	} else if col == board.width - 1 && combined_states & GOING_RIGHT > 0 {
-		combined_states = combined_states & (!GOING_RIGHT) | GOING_LEFT;
-	}
-	if row == 0 && combined_states & GOING_UP > 0 {
-		combined_states = combined_states & (!GOING_UP) | GOING_DOWN;
-	} else if row == board.height - 1 && combined_states & GOING_DOWN > 0 {
-		combined_states = combined_states & (!GOING_DOWN) | GOING_UP;
+	if board.boundary.reflects_particles() {
+		if col == 0 && combined_states & GOING_LEFT > 0 {
+			combined_states = combined_states & (!GOING_LEFT) | GOING_RIGHT;
+		} else if col == board.width - 1 && combined_states & GOING_RIGHT > 0 {
+			combined_states = combined_states & (!GOING_RIGHT) | GOING_LEFT;
+		}
+		if row == 0 && combined_states & GOING_UP > 0 {
+			combined_states = combined_states & (!GOING_UP) | GOING_DOWN;
+		} else if row == board.height - 1 && combined_states & GOING_DOWN > 0 {
+			combined_states = combined_states & (!GOING_DOWN) | GOING_UP;
+		}
 	}
 
 	if combined_states != 0 {
 		combined_states |= POPULATED;
 	}
 
-	Cell {
-		ruleset: Ruleset::LatticeGas,
-		state: combined_states,
-	}
+	combined_states
 }
@@ -0,0 +1,377 @@
+use crate::rules::packed::Counts;
+use crate::rules::{Rule, Visibility};
+use crate::world::Board;
+use nannou::color::LinSrgba;
+use nannou::rand;
+use std::fmt::Write;
+
+/// A "Life-like" outer totalistic rule, described by the neighbor counts
+/// which cause a dead cell to be born and a live cell to survive. Each count
+/// from 0 to 8 is a bit in the corresponding mask.
+#[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct LifeLike {
+	birth: u16,
+	survival: u16,
+}
+
+impl LifeLike {
+	pub const HIGHLIFE: LifeLike = LifeLike::new(&[3, 6], &[2, 3]);
+	pub const DAY_AND_NIGHT: LifeLike = LifeLike::new(&[3, 6, 7, 8], &[3, 4, 6, 7, 8]);
+	pub const MAZE: LifeLike = LifeLike::new(&[3], &[1, 2, 3, 4, 5]);
+
+	pub const fn new(birth: &[u8], survival: &[u8]) -> LifeLike {
+		LifeLike {
+			birth: counts_to_mask(birth),
+			survival: counts_to_mask(survival),
+		}
+	}
+
+	/// Parses a rule in B/S notation, like `B3/S23`, `B2/S` or `B35678/S5678`.
+	/// The birth and survival halves may come in either order, and case
+	/// doesn't matter.
+	pub fn parse(rule: &str) -> Result<LifeLike, ParseRuleError> {
+		let mut birth = None;
+		let mut survival = None;
+
+		for part in rule.trim().split('/') {
+			let mut chars = part.trim().chars();
+			let target = match chars.next() {
+				Some('B' | 'b') => &mut birth,
+				Some('S' | 's') => &mut survival,
+				Some(c) => return Err(ParseRuleError::UnexpectedCharacter(c)),
+				None => return Err(ParseRuleError::Empty),
+			};
+			if target.is_some() {
+				return Err(ParseRuleError::Duplicate);
+			}
+			let mut mask = 0u16;
+			for c in chars {
+				match c.to_digit(10) {
+					Some(count) if count <= 8 => mask |= 1 << count,
+					_ => return Err(ParseRuleError::UnexpectedCharacter(c)),
+				}
+			}
+			*target = Some(mask);
+		}
+
+		match (birth, survival) {
+			(Some(birth), Some(survival)) => Ok(LifeLike { birth, survival }),
+			(None, _) => Err(ParseRuleError::MissingBirth),
+			(_, None) => Err(ParseRuleError::MissingSurvival),
+		}
+	}
+
+	/// Computes the next state of a cell, setting the `0b10` bit if the cell
+	/// was alive this generation.
+	pub fn next_state(self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		let live_neighbors = count_live_neighbors(board, row, col, visibility);
+		let idx = row * board.width + col;
+
+		let is_alive = board[idx].state & 0b01 > 0;
+		if is_alive {
+			if self.survives(live_neighbors) {
+				State::Alive as u8 | 0b10
+			} else {
+				State::Dead as u8 | 0b10
+			}
+		} else {
+			if self.is_born(live_neighbors) {
+				State::Alive as u8
+			} else {
+				State::Dead as u8
+			}
+		}
+	}
+
+	/// Works out which of a word of packed cells are alive next generation,
+	/// from which are alive now and how many live neighbors each has.
+	pub fn next_alive_packed(self, alive: u64, counts: &Counts) -> u64 {
+		let mut born = 0;
+		let mut survives = 0;
+		for count in 0..=8 {
+			let matches = counts
+				.iter()
+				.enumerate()
+				.fold(!0, |matches, (bit, &counts)| {
+					matches
+						& if count >> bit & 1 > 0 {
+							counts
+						} else {
+							!counts
+						}
+				});
+			if self.is_born(count) {
+				born |= matches;
+			}
+			if self.survives(count) {
+				survives |= matches;
+			}
+		}
+		alive & survives | !alive & born
+	}
+
+	pub fn is_born(self, live_neighbors: u32) -> bool {
+		self.birth & (1 << live_neighbors) > 0
+	}
+
+	pub fn survives(self, live_neighbors: u32) -> bool {
+		self.survival & (1 << live_neighbors) > 0
+	}
+}
+
+impl Rule for LifeLike {
+	fn on(&self) -> u8 {
+		State::Alive as u8
+	}
+
+	fn off(&self) -> u8 {
+		State::Dead as u8
+	}
+
+	fn random(&self) -> u8 {
+		rand::random_range(0, 2)
+	}
+
+	fn life_like(&self) -> Option<LifeLike> {
+		Some(*self)
+	}
+
+	fn color(&self, state: u8) -> LinSrgba {
+		match state {
+			0b11 => LinSrgba::new(1.0, 0.8, 0.2, 1.0),
+			0b01 => LinSrgba::new(1.0, 0.5, 0.0, 1.0),
+			0b10 => LinSrgba::new(0.1, 0.05, 0.0, 1.0),
+			0b00 => LinSrgba::new(0.0, 0.0, 0.0, 1.0),
+			_ => LinSrgba::new(0.0, 0.0, 0.0, 1.0),
+		}
+	}
+
+	fn rule_color(&self) -> LinSrgba {
+		LinSrgba::new(1.0, 0.5, 0.0, 0.125)
+	}
+
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		self.next_state(board, row, col, visibility)
+	}
+
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
+		write!(output, "{:02b}", state)
+	}
+}
+
+impl std::fmt::Display for LifeLike {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(fmt, "B")?;
+		write_counts(fmt, self.birth)?;
+		write!(fmt, "/S")?;
+		write_counts(fmt, self.survival)
+	}
+}
+
+impl std::fmt::Debug for LifeLike {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		std::fmt::Display::fmt(self, fmt)
+	}
+}
+
+impl std::str::FromStr for LifeLike {
+	type Err = ParseRuleError;
+	fn from_str(rule: &str) -> Result<Self, Self::Err> {
+		LifeLike::parse(rule)
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRuleError {
+	Empty,
+	Duplicate,
+	MissingBirth,
+	MissingSurvival,
+	MissingStateCount,
+	InvalidStateCount,
+	InvalidRange,
+	InvalidSegment(char),
+	UnexpectedCharacter(char),
+}
+
+impl std::fmt::Display for ParseRuleError {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			ParseRuleError::Empty => write!(fmt, "empty rule segment"),
+			ParseRuleError::Duplicate => write!(fmt, "rule segment given more than once"),
+			ParseRuleError::MissingBirth => write!(fmt, "missing birth (B) segment"),
+			ParseRuleError::MissingSurvival => write!(fmt, "missing survival (S) segment"),
+			ParseRuleError::MissingStateCount => write!(fmt, "missing state count segment"),
+			ParseRuleError::InvalidStateCount => write!(fmt, "invalid state count"),
+			ParseRuleError::InvalidRange => write!(fmt, "invalid range"),
+			ParseRuleError::InvalidSegment(c) => write!(fmt, "invalid value for {c} segment"),
+			ParseRuleError::UnexpectedCharacter(c) => write!(fmt, "unexpected character {c:?}"),
+		}
+	}
+}
+
+impl std::error::Error for ParseRuleError {}
+
+#[derive(PartialEq, Eq)]
+#[repr(u8)]
+enum State {
+	Dead = 0,
+	Alive = 1,
+}
+
+const fn counts_to_mask(counts: &[u8]) -> u16 {
+	let mut mask = 0;
+	let mut i = 0;
+	while i < counts.len() {
+		mask |= 1 << counts[i];
+		i += 1;
+	}
+	mask
+}
+
+fn write_counts(fmt: &mut std::fmt::Formatter, mask: u16) -> std::fmt::Result {
+	for count in 0..=8 {
+		if mask & (1 << count) > 0 {
+			write!(fmt, "{count}")?;
+		}
+	}
+	Ok(())
+}
+
+fn count_live_row_neighbors(board: &Board, row: usize, col: usize, exclude_center: bool) -> u8 {
+	let idx = row * board.width + col;
+
+	let mut live = if exclude_center {
+		0
+	} else {
+		board[idx].state & 0b01
+	};
+
+	live <<= 1;
+	live |= board[idx - 1].state & 0b01;
+	live <<= 1;
+	live |= board[idx + 1].state & 0b01;
+
+	live
+}
+
+/// Counts the live cells in the Moore neighborhood of a cell, following the
+/// board's boundary past its edges. Weighted counts are kept between 0 and 8.
+pub(super) fn count_live_neighbors(
+	board: &Board,
+	row: usize,
+	col: usize,
+	visibility: Visibility,
+) -> u32 {
+	if !visibility.is_uniform() {
+		let weighted = MOORE_NEIGHBORHOOD
+			.iter()
+			.map(|&(dr, dc)| visibility.neighbor(board, row, col, dr, dc))
+			.sum::<i32>();
+		return weighted.clamp(0, 8) as u32;
+	}
+
+	if board.is_edge(row, col) {
+		return MOORE_NEIGHBORHOOD
+			.iter()
+			.map(|&(dr, dc)| (board.neighbor_state(row, col, dr, dc) & 0b01) as u32)
+			.sum();
+	}
+
+	let mut live_neighbors = count_live_row_neighbors(board, row, col, true) as u32;
+
+	live_neighbors <<= 3;
+	live_neighbors |= count_live_row_neighbors(board, row - 1, col, false) as u32;
+	live_neighbors <<= 3;
+	live_neighbors |= count_live_row_neighbors(board, row + 1, col, false) as u32;
+
+	live_neighbors.count_ones()
+}
+
+pub(super) const MOORE_NEIGHBORHOOD: [(isize, isize); 8] = [
+	(-1, -1),
+	(-1, 0),
+	(-1, 1),
+	(0, -1),
+	(0, 1),
+	(1, -1),
+	(1, 0),
+	(1, 1),
+];
+
+#[cfg(test)]
+mod test {
+	use super::{count_live_neighbors, LifeLike, ParseRuleError};
+	use crate::rules::{Interactions, Ruleset, Visibility};
+	use crate::world::{Board, Boundary, Cell};
+
+	#[test]
+	fn parse_reads_birth_and_survival() {
+		assert_eq!(LifeLike::parse("B36/S23"), Ok(LifeLike::HIGHLIFE));
+		assert_eq!(LifeLike::parse("s23/b36"), Ok(LifeLike::HIGHLIFE));
+		assert_eq!(LifeLike::parse("B2/S"), Ok(LifeLike::new(&[2], &[])));
+		assert_eq!(
+			LifeLike::parse("B35678/S5678"),
+			Ok(LifeLike::new(&[3, 5, 6, 7, 8], &[5, 6, 7, 8]))
+		);
+	}
+
+	#[test]
+	fn parse_rejects_malformed_rules() {
+		assert_eq!(LifeLike::parse("B3"), Err(ParseRuleError::MissingSurvival));
+		assert_eq!(LifeLike::parse("S23"), Err(ParseRuleError::MissingBirth));
+		assert_eq!(
+			LifeLike::parse("B9/S23"),
+			Err(ParseRuleError::UnexpectedCharacter('9'))
+		);
+		assert_eq!(LifeLike::parse("B3/B3"), Err(ParseRuleError::Duplicate));
+	}
+
+	#[test]
+	fn display_round_trips() {
+		assert_eq!(LifeLike::DAY_AND_NIGHT.to_string(), "B3678/S34678");
+		assert_eq!(
+			LifeLike::parse(&LifeLike::MAZE.to_string()),
+			Ok(LifeLike::MAZE)
+		);
+	}
+
+	#[test]
+	fn neighbors_are_weighted_by_visibility() {
+		#[rustfmt::skip]
+		let cells = [
+			(Ruleset::SEEDS, 0b01), (Ruleset::SEEDS, 0b01), (Ruleset::SEEDS, 0b01),
+			(Ruleset::LIFE, 0b01), (Ruleset::LIFE, 0b00), (Ruleset::LIFE, 0b00),
+			(Ruleset::LIFE, 0b00), (Ruleset::LIFE, 0b00), (Ruleset::LIFE, 0b00),
+		]
+		.into_iter()
+		.map(|(ruleset, state)| Cell { ruleset, state })
+		.collect::<Vec<_>>();
+		let board = Board {
+			cells,
+			width: 3,
+			height: 3,
+			generation: 0,
+			boundary: Boundary::Dead,
+		};
+
+		assert_eq!(count_live_neighbors(&board, 1, 1, Visibility::ALL), 4);
+
+		let isolated = Interactions::isolated(Ruleset::SEEDS.index() + 1);
+		assert_eq!(
+			count_live_neighbors(&board, 1, 1, isolated.seen_by(Ruleset::LIFE)),
+			1
+		);
+
+		let mut hostile = Interactions::default();
+		hostile.set(Ruleset::LIFE, Ruleset::SEEDS, -1);
+		assert_eq!(
+			count_live_neighbors(&board, 1, 1, hostile.seen_by(Ruleset::LIFE)),
+			0
+		);
+		assert_eq!(
+			count_live_neighbors(&board, 1, 1, hostile.seen_by(Ruleset::SEEDS)),
+			4
+		);
+	}
+}
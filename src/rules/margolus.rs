@@ -0,0 +1,346 @@
+use crate::rules::{Rule, Visibility};
+use crate::world::{Board, Boundary};
+use nannou::color::LinSrgba;
+use nannou::rand;
+use std::fmt::Write;
+
+/// A block rule on the Margolus neighborhood, like the ones the CAM-6 ran.
+/// The board is partitioned into 2x2 blocks, and the partition shifts by one
+/// cell diagonally every generation. Each block is replaced all at once by
+/// looking up its contents in a 16-entry table.
+///
+/// Blocks are indexed by their live cells: `0b0001` is the top left cell,
+/// `0b0010` top right, `0b0100` bottom left and `0b1000` bottom right.
+#[derive(Copy, Clone)]
+pub struct Margolus {
+	name: &'static str,
+	table: [u8; 16],
+}
+
+impl Margolus {
+	pub const CRITTERS: Margolus = Margolus::new("Critters", critters_table());
+	pub const TRON: Margolus = Margolus::new("Tron", tron_table());
+	pub const BILLIARD_BALL: Margolus = Margolus::new("BilliardBall", billiard_ball_table());
+	pub const HPP_GAS: Margolus = Margolus::new("HppGas", hpp_gas_table());
+
+	pub const fn new(name: &'static str, table: [u8; 16]) -> Margolus {
+		Margolus { name, table }
+	}
+
+	/// Computes the next state of a cell by running its whole block through
+	/// the lookup table. Blocks that hang off the edge of the board wrap
+	/// around a torus, which needs an even width and height to keep blocks
+	/// from overlapping. With any other boundary they're left as they are.
+	/// Cells of rulesets the rule can't see are empty space in the block.
+	pub fn next_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		let (top, left) = Margolus::block_of(board, row, col);
+		let position = (row as isize - top) * 2 + (col as isize - left);
+		Margolus::cell_of(
+			self.next_block(board, top, left, visibility),
+			position as usize,
+			board[row * board.width + col].state,
+		)
+	}
+
+	/// The row and column of the top left cell of the block a cell belongs
+	/// to this generation. A block in the odd partition starts at an odd row
+	/// and column, so the first row and column belong to blocks hanging off
+	/// the top left.
+	pub fn block_of(board: &Board, row: usize, col: usize) -> (isize, isize) {
+		let offset = (board.generation % 2) as isize;
+		let top = ((row as isize - offset) & !1) + offset;
+		let left = ((col as isize - offset) & !1) + offset;
+		(top, left)
+	}
+
+	/// Runs the block with its top left cell at `top` and `left` through the
+	/// lookup table, and returns its live cells afterwards. Blocks hanging off
+	/// the edge of a board that isn't a torus are `None`.
+	pub fn next_block(
+		&self,
+		board: &Board,
+		top: isize,
+		left: isize,
+		visibility: Visibility,
+	) -> Option<u8> {
+		let hangs_off_edge = top < 0
+			|| left < 0
+			|| top as usize + 1 >= board.height
+			|| left as usize + 1 >= board.width;
+		if hangs_off_edge && board.boundary != Boundary::Torus {
+			return None;
+		}
+
+		let mut block = 0;
+		for (bit, (y, x)) in [(0, 0), (0, 1), (1, 0), (1, 1)].into_iter().enumerate() {
+			let live = visibility.neighbor(board, 0, 0, top + y, left + x) > 0;
+			block |= (live as u8) << bit;
+		}
+		Some(self.table[block as usize])
+	}
+
+	/// The next state of the cell at `position` in a block that came out of
+	/// `next_block`, given its state now
+	pub fn cell_of(block: Option<u8>, position: usize, state: u8) -> u8 {
+		let was_alive = state & 0b01;
+		match block {
+			Some(block) => (block >> position) & 0b01 | was_alive << 1,
+			None => was_alive,
+		}
+	}
+}
+
+impl Rule for Margolus {
+	fn on(&self) -> u8 {
+		0b01
+	}
+
+	fn off(&self) -> u8 {
+		0b00
+	}
+
+	fn random(&self) -> u8 {
+		rand::random_range(0, 2)
+	}
+
+	fn margolus(&self) -> Option<Margolus> {
+		Some(*self)
+	}
+
+	/// Blocks are drawn on a different grid every other generation
+	fn uses_generation(&self) -> bool {
+		true
+	}
+
+	fn color(&self, state: u8) -> LinSrgba {
+		match state {
+			0b11 => LinSrgba::new(0.9, 1.0, 0.9, 1.0),
+			0b01 => LinSrgba::new(0.4, 1.0, 0.6, 1.0),
+			0b10 => LinSrgba::new(0.0, 0.15, 0.1, 1.0),
+			_ => LinSrgba::new(0.0, 0.0, 0.0, 1.0),
+		}
+	}
+
+	fn rule_color(&self) -> LinSrgba {
+		LinSrgba::new(0.4, 1.0, 0.6, 0.125)
+	}
+
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		self.next_state(board, row, col, visibility)
+	}
+
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
+		write!(output, "{:02b}", state)
+	}
+}
+
+impl std::fmt::Debug for Margolus {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		fmt.write_str(self.name)
+	}
+}
+
+const TOP_LEFT: u8 = 0b0001;
+const TOP_RIGHT: u8 = 0b0010;
+const BOTTOM_LEFT: u8 = 0b0100;
+const BOTTOM_RIGHT: u8 = 0b1000;
+
+const fn rotate_180(block: u8) -> u8 {
+	let mut rotated = 0;
+	if block & TOP_LEFT > 0 {
+		rotated |= BOTTOM_RIGHT;
+	}
+	if block & TOP_RIGHT > 0 {
+		rotated |= BOTTOM_LEFT;
+	}
+	if block & BOTTOM_LEFT > 0 {
+		rotated |= TOP_RIGHT;
+	}
+	if block & BOTTOM_RIGHT > 0 {
+		rotated |= TOP_LEFT;
+	}
+	rotated
+}
+
+/// Blocks without exactly two live cells are inverted, and blocks with three
+/// live cells are also turned around.
+const fn critters_table() -> [u8; 16] {
+	let mut table = [0; 16];
+	let mut block: u8 = 0;
+	while block < 16 {
+		table[block as usize] = match block.count_ones() {
+			2 => block,
+			3 => rotate_180(!block & 0b1111),
+			_ => !block & 0b1111,
+		};
+		block += 1;
+	}
+	table
+}
+
+/// Blocks that are all alive or all dead are inverted.
+const fn tron_table() -> [u8; 16] {
+	let mut table = [0; 16];
+	let mut block: u8 = 0;
+	while block < 16 {
+		table[block as usize] = match block {
+			0b0000 => 0b1111,
+			0b1111 => 0b0000,
+			_ => block,
+		};
+		block += 1;
+	}
+	table
+}
+
+/// Lone balls move diagonally through the block, two balls colliding head on
+/// bounce off at right angles, and everything else stays still and acts as a
+/// mirror.
+const fn billiard_ball_table() -> [u8; 16] {
+	let mut table = [0; 16];
+	let mut block: u8 = 0;
+	while block < 16 {
+		table[block as usize] = match block {
+			TOP_LEFT | TOP_RIGHT | BOTTOM_LEFT | BOTTOM_RIGHT => rotate_180(block),
+			0b1001 => 0b0110,
+			0b0110 => 0b1001,
+			_ => block,
+		};
+		block += 1;
+	}
+	table
+}
+
+/// Every particle moves diagonally through the block, except for head on
+/// collisions, which turn at right angles.
+const fn hpp_gas_table() -> [u8; 16] {
+	let mut table = [0; 16];
+	let mut block: u8 = 0;
+	while block < 16 {
+		table[block as usize] = match block {
+			0b1001 => 0b0110,
+			0b0110 => 0b1001,
+			_ => rotate_180(block),
+		};
+		block += 1;
+	}
+	table
+}
+
+#[cfg(test)]
+mod test {
+	use super::Margolus;
+	use crate::rules::Visibility;
+	use crate::world::{Board, Boundary, Cell};
+
+	fn board(states: &[u8], width: usize, generation: u64) -> Board {
+		Board {
+			cells: states
+				.iter()
+				.map(|&state| Cell {
+					state,
+					..Default::default()
+				})
+				.collect(),
+			width,
+			height: states.len() / width,
+			generation,
+			boundary: Boundary::Dead,
+		}
+	}
+
+	fn step(rule: &Margolus, board: &Board) -> Vec<u8> {
+		(0..board.height)
+			.flat_map(|row| (0..board.width).map(move |col| (row, col)))
+			.map(|(row, col)| rule.next_state(board, row, col, Visibility::ALL) & 0b01)
+			.collect()
+	}
+
+	#[test]
+	fn partition_alternates_between_generations() {
+		#[rustfmt::skip]
+		let states = [
+			1, 0, 0, 0,
+			0, 0, 0, 0,
+			0, 0, 0, 0,
+			0, 0, 0, 0,
+		];
+
+		// On even generations, the ball is in the top left block and moves
+		// to its bottom right corner.
+		#[rustfmt::skip]
+		assert_eq!(step(&Margolus::BILLIARD_BALL, &board(&states, 4, 0)), vec![
+			0, 0, 0, 0,
+			0, 1, 0, 0,
+			0, 0, 0, 0,
+			0, 0, 0, 0,
+		]);
+
+		// On odd generations, the top row and left column are left out of the
+		// partition, so the ball doesn't move.
+		assert_eq!(
+			step(&Margolus::BILLIARD_BALL, &board(&states, 4, 1)),
+			states.to_vec()
+		);
+
+		#[rustfmt::skip]
+		let states = [
+			0, 0, 0, 0,
+			0, 1, 0, 0,
+			0, 0, 0, 0,
+			0, 0, 0, 0,
+		];
+		#[rustfmt::skip]
+		assert_eq!(step(&Margolus::BILLIARD_BALL, &board(&states, 4, 1)), vec![
+			0, 0, 0, 0,
+			0, 0, 0, 0,
+			0, 0, 1, 0,
+			0, 0, 0, 0,
+		]);
+	}
+
+	#[test]
+	fn blocks_wrap_around_a_torus() {
+		#[rustfmt::skip]
+		let states = [
+			1, 0, 0, 0,
+			0, 0, 0, 0,
+			0, 0, 0, 0,
+			0, 0, 0, 0,
+		];
+		let mut board = board(&states, 4, 1);
+		board.boundary = Boundary::Torus;
+
+		// The ball's block wraps around all four corners, and it moves across
+		// to the opposite one.
+		#[rustfmt::skip]
+		assert_eq!(step(&Margolus::BILLIARD_BALL, &board), vec![
+			0, 0, 0, 0,
+			0, 0, 0, 0,
+			0, 0, 0, 0,
+			0, 0, 0, 1,
+		]);
+	}
+
+	#[test]
+	fn critters_inverts_blocks_without_two_live_cells() {
+		let states = [1, 1, 0, 0];
+		assert_eq!(
+			step(&Margolus::CRITTERS, &board(&states, 2, 0)),
+			vec![1, 1, 0, 0]
+		);
+
+		let states = [0, 0, 0, 0];
+		assert_eq!(
+			step(&Margolus::CRITTERS, &board(&states, 2, 0)),
+			vec![1, 1, 1, 1]
+		);
+
+		// Three live cells invert to one, which is then turned around
+		let states = [1, 1, 1, 0];
+		assert_eq!(
+			step(&Margolus::CRITTERS, &board(&states, 2, 0)),
+			vec![1, 0, 0, 0]
+		);
+	}
+}
@@ -1,130 +1,471 @@
+mod anneal;
 mod anti_life;
 mod brians_brain;
+mod cyclic;
 mod diamoeba;
+mod fhp_gas;
+mod generations;
+mod interactions;
+mod larger_than_life;
 mod lattice_gas;
 mod life;
+mod life_like;
+mod margolus;
+mod packed;
 mod seeds;
+mod wireworld;
 
 use crate::world::{Board, Cell};
+pub use anneal::Anneal;
 pub use anti_life::AntiLife;
 pub use brians_brain::BriansBrain;
+pub use cyclic::Cyclic;
 pub use diamoeba::Diamoeba;
+pub use fhp_gas::FhpGas;
+pub use generations::Generations;
+pub use interactions::{Interactions, Visibility};
+pub use larger_than_life::{LargerThanLife, Neighborhood};
 pub use lattice_gas::LatticeGas;
 pub use life::Life;
+pub use life_like::{LifeLike, ParseRuleError};
+pub use margolus::Margolus;
 use nannou::color::LinSrgba;
+pub use packed::PackedBoard;
 pub use seeds::Seeds;
 use std::fmt::Write;
+pub use wireworld::Wireworld;
 
-#[repr(u8)]
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
-pub enum Ruleset {
-	Life,
-	AntiLife,
-	BriansBrain,
-	Seeds,
-	Diamoeba,
-	LatticeGas,
+/// A cellular automaton rule. Rules only deal in cell states; which ruleset a
+/// cell belongs to is tracked by the `Registry` the rule is registered with.
+pub trait Rule: std::fmt::Debug + Send + Sync {
+	/// The state painted by the brush
+	fn on(&self) -> u8;
+	/// The state a cleared board is filled with
+	fn off(&self) -> u8;
+	fn random(&self) -> u8;
+	/// The state a cell under the brush is painted with. `first_touch` is only
+	/// true the first time a brush stroke passes over the cell.
+	fn paint(&self, _state: u8, _first_touch: bool) -> u8 {
+		self.on()
+	}
+	/// Whether a cell in this state counts as present when growth lets cells
+	/// that aren't alive claim territory. By default that's any state with
+	/// something in it, like a history of having been alive.
+	fn is_present(&self, state: u8) -> bool {
+		state != 0
+	}
+	/// What a cell in this state amounts to for a rule that doesn't know its
+	/// encoding. By default the lowest bit is life, and anything else left in
+	/// the state is a history of having been alive.
+	fn export(&self, state: u8) -> Vitality {
+		if state & 0b01 > 0 {
+			Vitality::Alive
+		} else if state != 0 {
+			Vitality::WasAlive
+		} else {
+			Vitality::Dead
+		}
+	}
+	/// The state a cell taken over from another rule starts in
+	fn import(&self, vitality: Vitality) -> u8 {
+		match vitality {
+			Vitality::Alive => 0b01,
+			Vitality::WasAlive => 0b10,
+			Vitality::Dead => 0b00,
+		}
+	}
+	/// Energy a cell in this state burns each generation when the world has
+	/// an energy field. By default only live cells burn any.
+	fn metabolism(&self, state: u8) -> f32 {
+		(state & 0b01) as f32
+	}
+	/// The state a cell takes when it runs out of energy
+	fn starve(&self, _state: u8) -> u8 {
+		self.import(Vitality::WasAlive)
+	}
+	/// The rule as a plain two-state Life-like rule, if it is one that keeps
+	/// whether the cell was alive in the `0b10` bit. Cells of these rules can
+	/// be worked out 64 at a time on a `PackedBoard`.
+	fn life_like(&self) -> Option<LifeLike> {
+		None
+	}
+	/// The rule as a Margolus block rule, if it is one. Cells of these rules
+	/// can be worked out a whole block at a time.
+	fn margolus(&self) -> Option<Margolus> {
+		None
+	}
+	/// How many cells away the neighbors that decide a cell's next state
+	/// can be
+	fn radius(&self) -> usize {
+		1
+	}
+	/// Whether next states depend on the generation as well as the cells
+	/// around, so that a cell can change while nothing near it does
+	fn uses_generation(&self) -> bool {
+		false
+	}
+	/// Whether next states depend on the energy left in the cell, when the
+	/// world has an energy field
+	fn uses_energy(&self) -> bool {
+		false
+	}
+	fn color(&self, state: u8) -> LinSrgba;
+	/// Translucent color blended over the board to show territory
+	fn rule_color(&self) -> LinSrgba;
+	/// Computes the next state of a cell. `visibility` says how much the
+	/// cells of each ruleset count as live neighbors.
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8;
+	/// Computes the next state of a cell with `energy` left in it, or `None`
+	/// without an energy field. Rules that use energy work it out here, and
+	/// the rest leave it to `next_cell_state`.
+	fn next_cell_state_with_energy(
+		&self,
+		board: &Board,
+		row: usize,
+		col: usize,
+		visibility: Visibility,
+		_energy: Option<f32>,
+	) -> u8 {
+		self.next_cell_state(board, row, col, visibility)
+	}
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result;
+}
+
+/// The common ground between the states of different rules, used to carry
+/// a cell over when it changes ruleset.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Vitality {
+	Dead,
+	WasAlive,
+	Alive,
 }
 
-const VARIANTS: &[Ruleset] = &[
-	Ruleset::Life,
-	Ruleset::AntiLife,
-	Ruleset::BriansBrain,
-	Ruleset::Seeds,
-	Ruleset::Diamoeba,
-	Ruleset::LatticeGas,
-];
+/// Compact id of a rule in a `Registry`, small enough to keep in every cell.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct Ruleset(u8);
+
+impl Ruleset {
+	pub const LIFE: Ruleset = Ruleset(0);
+	pub const ANTI_LIFE: Ruleset = Ruleset(1);
+	pub const BRIANS_BRAIN: Ruleset = Ruleset(2);
+	pub const SEEDS: Ruleset = Ruleset(3);
+	pub const DIAMOEBA: Ruleset = Ruleset(4);
+	pub const ANNEAL: Ruleset = Ruleset(5);
+	pub const LATTICE_GAS: Ruleset = Ruleset(6);
+	pub const HIGHLIFE: Ruleset = Ruleset(7);
+	pub const DAY_AND_NIGHT: Ruleset = Ruleset(8);
+	pub const MAZE: Ruleset = Ruleset(9);
+	pub const STAR_WARS: Ruleset = Ruleset(10);
+	pub const FROGS: Ruleset = Ruleset(11);
+	pub const STICKS: Ruleset = Ruleset(12);
+	pub const CRITTERS: Ruleset = Ruleset(13);
+	pub const TRON: Ruleset = Ruleset(14);
+	pub const BILLIARD_BALL: Ruleset = Ruleset(15);
+	pub const HPP_GAS: Ruleset = Ruleset(16);
+	pub const FHP_GAS: Ruleset = Ruleset(17);
+	pub const BOSCO: Ruleset = Ruleset(18);
+	pub const MAJORITY: Ruleset = Ruleset(19);
+	pub const WIREWORLD: Ruleset = Ruleset(20);
+	pub const THREE_ONE_THREE: Ruleset = Ruleset(21);
+	pub const CCA: Ruleset = Ruleset(22);
+	pub const SPIRALS: Ruleset = Ruleset(23);
 
-impl Default for Ruleset {
-	fn default() -> Ruleset {
-		Ruleset::Life
+	pub fn index(self) -> usize {
+		self.0 as usize
 	}
 }
 
-impl Ruleset {
-	pub fn on(&self) -> Cell {
-		match self {
-			Ruleset::Life => Life::alive(),
-			Ruleset::AntiLife => AntiLife::dead(),
-			Ruleset::BriansBrain => BriansBrain::firing(),
-			Ruleset::Seeds => Seeds::alive(),
-			Ruleset::Diamoeba => Diamoeba::alive(),
-			Ruleset::LatticeGas => LatticeGas::random_populated(),
+#[derive(Debug)]
+pub struct Registry {
+	rules: Vec<Box<dyn Rule>>,
+	interactions: Interactions,
+}
+
+impl Default for Registry {
+	fn default() -> Registry {
+		// The order of the first rules must match the `Ruleset` constants
+		Registry {
+			rules: vec![
+				Box::new(Life),
+				Box::new(AntiLife),
+				Box::new(BriansBrain),
+				Box::new(Seeds),
+				Box::new(Diamoeba),
+				Box::new(Anneal),
+				Box::new(LatticeGas),
+				Box::new(LifeLike::HIGHLIFE),
+				Box::new(LifeLike::DAY_AND_NIGHT),
+				Box::new(LifeLike::MAZE),
+				Box::new(Generations::STAR_WARS),
+				Box::new(Generations::FROGS),
+				Box::new(Generations::STICKS),
+				Box::new(Margolus::CRITTERS),
+				Box::new(Margolus::TRON),
+				Box::new(Margolus::BILLIARD_BALL),
+				Box::new(Margolus::HPP_GAS),
+				Box::new(FhpGas),
+				Box::new(LargerThanLife::BOSCO),
+				Box::new(LargerThanLife::MAJORITY),
+				Box::new(Wireworld),
+				Box::new(Cyclic::THREE_ONE_THREE),
+				Box::new(Cyclic::CCA),
+				Box::new(Cyclic::SPIRALS.with_gradient(vec![
+					[0.05, 0.0, 0.2],
+					[0.1, 0.3, 0.9],
+					[0.4, 0.9, 1.0],
+					[1.0, 1.0, 1.0],
+				])),
+			],
+			interactions: Interactions::default(),
+		}
+	}
+}
+
+impl Registry {
+	/// Adds a rule, returning its id, or `None` if the registry is full.
+	pub fn register(&mut self, rule: Box<dyn Rule>) -> Option<Ruleset> {
+		let id = u8::try_from(self.rules.len()).ok()?;
+		self.rules.push(rule);
+		Some(Ruleset(id))
+	}
+
+	pub fn len(&self) -> usize {
+		self.rules.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.rules.is_empty()
+	}
+
+	pub fn interactions(&self) -> &Interactions {
+		&self.interactions
+	}
+
+	pub fn set_interactions(&mut self, interactions: Interactions) {
+		self.interactions = interactions;
+	}
+
+	pub fn get(&self, ruleset: Ruleset) -> &dyn Rule {
+		self.rules[ruleset.index()].as_ref()
+	}
+
+	pub fn on(&self, ruleset: Ruleset) -> Cell {
+		Cell {
+			ruleset,
+			state: self.get(ruleset).on(),
+		}
+	}
+
+	pub fn off(&self, ruleset: Ruleset) -> Cell {
+		Cell {
+			ruleset,
+			state: self.get(ruleset).off(),
 		}
 	}
 
-	pub fn off(&self) -> Cell {
-		match self {
-			Ruleset::Life => Life::dead(),
-			Ruleset::AntiLife => AntiLife::alive(),
-			Ruleset::BriansBrain => BriansBrain::dead(),
-			Ruleset::Seeds => Seeds::dead(),
-			Ruleset::Diamoeba => Diamoeba::dead(),
-			Ruleset::LatticeGas => LatticeGas::empty(),
+	pub fn random(&self, ruleset: Ruleset) -> Cell {
+		Cell {
+			ruleset,
+			state: self.get(ruleset).random(),
 		}
 	}
 
-	pub fn random(&self) -> Cell {
-		match self {
-			Ruleset::Life => Life::random(),
-			Ruleset::AntiLife => AntiLife::random(),
-			Ruleset::BriansBrain => BriansBrain::random(),
-			Ruleset::Seeds => Seeds::random(),
-			Ruleset::Diamoeba => Diamoeba::random(),
-			Ruleset::LatticeGas => LatticeGas::random(),
+	pub fn paint(&self, cell: Cell, first_touch: bool) -> Cell {
+		Cell {
+			ruleset: cell.ruleset,
+			state: self.get(cell.ruleset).paint(cell.state, first_touch),
 		}
 	}
 
-	pub fn color(&self, cell: Cell) -> LinSrgba {
-		match self {
-			Ruleset::Life => Life::color(cell),
-			Ruleset::AntiLife => AntiLife::color(cell),
-			Ruleset::BriansBrain => BriansBrain::color(cell),
-			Ruleset::Seeds => Seeds::color(cell),
-			Ruleset::Diamoeba => Diamoeba::color(cell),
-			Ruleset::LatticeGas => LatticeGas::color(cell),
+	pub fn is_present(&self, cell: Cell) -> bool {
+		self.get(cell.ruleset).is_present(cell.state)
+	}
+
+	/// Hands a cell over to `ruleset`, translating its state into the
+	/// closest one the new rule has.
+	pub fn translate(&self, cell: Cell, ruleset: Ruleset) -> Cell {
+		if cell.ruleset == ruleset {
+			return cell;
+		}
+		self.import(ruleset, self.export(cell))
+	}
+
+	pub fn export(&self, cell: Cell) -> Vitality {
+		self.get(cell.ruleset).export(cell.state)
+	}
+
+	pub fn import(&self, ruleset: Ruleset, vitality: Vitality) -> Cell {
+		Cell {
+			ruleset,
+			state: self.get(ruleset).import(vitality),
 		}
 	}
 
-	pub fn rule_color(&self) -> LinSrgba {
-		match self {
-			Ruleset::Life => LinSrgba::new(1.0, 0.0, 0.0, 0.125),
-			Ruleset::AntiLife => LinSrgba::new(0.0, 1.0, 0.0, 0.125),
-			Ruleset::BriansBrain => LinSrgba::new(0.0, 1.0, 1.0, 0.125),
-			Ruleset::Seeds => LinSrgba::new(0.0, 1.0, 0.5, 0.125),
-			Ruleset::Diamoeba => LinSrgba::new(0.0, 0.0, 1.0, 0.125),
-			Ruleset::LatticeGas => LinSrgba::new(1.0, 1.0, 1.0, 0.125),
+	pub fn life_like(&self, ruleset: Ruleset) -> Option<LifeLike> {
+		self.get(ruleset).life_like()
+	}
+
+	pub fn margolus(&self, ruleset: Ruleset) -> Option<Margolus> {
+		self.get(ruleset).margolus()
+	}
+
+	/// The farthest any rule looks for neighbors
+	pub fn radius(&self) -> usize {
+		self.rules
+			.iter()
+			.map(|rule| rule.radius())
+			.max()
+			.unwrap_or(0)
+	}
+
+	pub fn uses_generation(&self, ruleset: Ruleset) -> bool {
+		self.get(ruleset).uses_generation()
+	}
+
+	pub fn uses_energy(&self, ruleset: Ruleset) -> bool {
+		self.get(ruleset).uses_energy()
+	}
+
+	pub fn metabolism(&self, cell: Cell) -> f32 {
+		self.get(cell.ruleset).metabolism(cell.state)
+	}
+
+	pub fn starve(&self, cell: Cell) -> Cell {
+		Cell {
+			ruleset: cell.ruleset,
+			state: self.get(cell.ruleset).starve(cell.state),
 		}
 	}
 
-	pub fn next_cell_state(&self, board: &Board, row: usize, col: usize) -> Cell {
-		match self {
-			Ruleset::Life => Life::next_cell_state(board, row, col),
-			Ruleset::AntiLife => AntiLife::next_cell_state(board, row, col),
-			Ruleset::BriansBrain => BriansBrain::next_cell_state(board, row, col),
-			Ruleset::Seeds => Seeds::next_cell_state(board, row, col),
-			Ruleset::Diamoeba => Diamoeba::next_cell_state(board, row, col),
-			Ruleset::LatticeGas => LatticeGas::next_cell_state(board, row, col),
+	pub fn color(&self, ruleset: Ruleset, cell: Cell) -> LinSrgba {
+		self.get(ruleset).color(cell.state)
+	}
+
+	pub fn rule_color(&self, ruleset: Ruleset) -> LinSrgba {
+		self.get(ruleset).rule_color()
+	}
+
+	/// Computes the next state of a cell as a cell of `ruleset`, with
+	/// `energy` left in it if the world has an energy field
+	pub fn next_cell_state(
+		&self,
+		ruleset: Ruleset,
+		board: &Board,
+		row: usize,
+		col: usize,
+		energy: Option<f32>,
+	) -> Cell {
+		Cell {
+			ruleset,
+			state: self.get(ruleset).next_cell_state_with_energy(
+				board,
+				row,
+				col,
+				self.interactions.seen_by(ruleset),
+				energy,
+			),
 		}
 	}
 
 	pub fn write_debug(&self, cell: Cell) -> String {
+		let rule = self.get(cell.ruleset);
 		let mut output = String::new();
-		write!(&mut output, "{:?}(", cell.ruleset).unwrap();
-		match self {
-			Ruleset::Life => Life::write_debug(&mut output, cell.state),
-			Ruleset::AntiLife => AntiLife::write_debug(&mut output, cell.state),
-			Ruleset::BriansBrain => BriansBrain::write_debug(&mut output, cell.state),
-			Ruleset::Seeds => Seeds::write_debug(&mut output, cell.state),
-			Ruleset::Diamoeba => Diamoeba::write_debug(&mut output, cell.state),
-			Ruleset::LatticeGas => LatticeGas::write_debug(&mut output, cell.state),
-		}
-		.unwrap();
+		write!(&mut output, "{:?}(", rule).unwrap();
+		rule.write_debug(&mut output, cell.state).unwrap();
 		write!(&mut output, ")").unwrap();
 		output
 	}
 
-	pub fn next(&self) -> Ruleset {
-		VARIANTS[(*self as u8 as usize + 1) % VARIANTS.len()]
+	pub fn name(&self, ruleset: Ruleset) -> String {
+		format!("{:?}", self.get(ruleset))
+	}
+
+	pub fn rulesets(&self) -> impl Iterator<Item = Ruleset> {
+		(0..self.rules.len()).map(|idx| Ruleset(idx as u8))
+	}
+
+	pub fn next(&self, ruleset: Ruleset) -> Ruleset {
+		Ruleset(((ruleset.index() + 1) % self.rules.len()) as u8)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{Cyclic, LifeLike, Registry, Ruleset, Wireworld};
+	use crate::world::Cell;
+
+	#[test]
+	fn builtin_ruleset_ids_match_registry_order() {
+		let rules = Registry::default();
+		assert_eq!(rules.name(Ruleset::LIFE), "Life");
+		assert_eq!(rules.name(Ruleset::ANTI_LIFE), "AntiLife");
+		assert_eq!(rules.name(Ruleset::BRIANS_BRAIN), "BriansBrain");
+		assert_eq!(rules.name(Ruleset::SEEDS), "Seeds");
+		assert_eq!(rules.name(Ruleset::DIAMOEBA), "Diamoeba");
+		assert_eq!(rules.name(Ruleset::ANNEAL), "Anneal");
+		assert_eq!(rules.name(Ruleset::LATTICE_GAS), "LatticeGas");
+		assert_eq!(rules.name(Ruleset::HIGHLIFE), "B36/S23");
+		assert_eq!(rules.name(Ruleset::DAY_AND_NIGHT), "B3678/S34678");
+		assert_eq!(rules.name(Ruleset::MAZE), "B3/S12345");
+		assert_eq!(rules.name(Ruleset::STAR_WARS), "B2/S345/4");
+		assert_eq!(rules.name(Ruleset::FROGS), "B34/S12/3");
+		assert_eq!(rules.name(Ruleset::STICKS), "B2/S3456/6");
+		assert_eq!(rules.name(Ruleset::CRITTERS), "Critters");
+		assert_eq!(rules.name(Ruleset::TRON), "Tron");
+		assert_eq!(rules.name(Ruleset::BILLIARD_BALL), "BilliardBall");
+		assert_eq!(rules.name(Ruleset::HPP_GAS), "HppGas");
+		assert_eq!(rules.name(Ruleset::FHP_GAS), "FhpGas");
+		assert_eq!(rules.name(Ruleset::BOSCO), "R5,C0,M1,S34..58,B34..45,NM");
+		assert_eq!(rules.name(Ruleset::MAJORITY), "R4,C0,M1,S41..81,B41..81,NM");
+		assert_eq!(rules.name(Ruleset::WIREWORLD), "Wireworld");
+		assert_eq!(rules.name(Ruleset::THREE_ONE_THREE), "R1/T3/C3/NM");
+		assert_eq!(rules.name(Ruleset::CCA), "R1/T1/C14/NN");
+		assert_eq!(rules.name(Ruleset::SPIRALS), "R3/T5/C8/NM");
+		assert_eq!(rules.len(), 24);
+	}
+
+	#[test]
+	fn registered_rules_get_new_ids() {
+		let mut rules = Registry::default();
+		let maze = rules
+			.register(Box::new("B3/S12345".parse::<LifeLike>().unwrap()))
+			.unwrap();
+		assert_eq!(rules.name(maze), "B3/S12345");
+		assert_eq!(rules.next(maze), Ruleset::LIFE);
+	}
+
+	#[test]
+	fn translated_cells_keep_their_vitality() {
+		let mut rules = Registry::default();
+		let wireworld = rules.register(Box::new(Wireworld)).unwrap();
+		let cyclic = rules.register(Box::new(Cyclic::CCA)).unwrap();
+		let cell = |ruleset, state| Cell { ruleset, state };
+
+		// Live cells stay alive, and history stays history
+		let life = cell(Ruleset::LIFE, 0b01);
+		assert_eq!(rules.translate(life, Ruleset::ANTI_LIFE).state, 0b01);
+		let dying = cell(Ruleset::BRIANS_BRAIN, 0b10);
+		assert_eq!(rules.translate(dying, Ruleset::LIFE).state, 0b10);
+
+		// Gas particles are alive whichever way they're moving
+		let particle = cell(Ruleset::LATTICE_GAS, 0b10000);
+		assert_eq!(rules.translate(particle, Ruleset::LIFE).state, 0b01);
+		let imported = rules.translate(life, Ruleset::LATTICE_GAS);
+		assert_eq!(rules.translate(imported, Ruleset::LIFE).state, 0b01);
+
+		// Colors and conductors aren't life
+		assert_eq!(
+			rules.translate(cell(cyclic, 3 << 1), Ruleset::LIFE).state,
+			0
+		);
+		assert_eq!(
+			rules.translate(cell(wireworld, 0b100), Ruleset::LIFE).state,
+			0
+		);
+		assert_eq!(rules.translate(life, wireworld).state, 0b001);
+
+		// A cell that stays in its ruleset is left alone
+		let colored = cell(cyclic, 3 << 1);
+		assert_eq!(rules.translate(colored, cyclic).state, colored.state);
 	}
 }
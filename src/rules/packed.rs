@@ -0,0 +1,142 @@
+use crate::world::Board;
+
+/// Which cells of a board are alive, packed 64 to a word so that Life-like
+/// rules can work out a whole word of cells at once. Every row has a border
+/// cell at either end, and there's a border row above and below, holding
+/// whatever lies just past that edge of the board.
+#[derive(Clone, Debug, Default)]
+pub struct PackedBoard {
+	words_per_row: usize,
+	words: Vec<u64>,
+}
+
+/// Live neighbor counts for a word of cells, bit-sliced: bit `i` of
+/// `counts[k]` is bit `k` of the count for the `i`th cell of the word
+pub type Counts = [u64; 4];
+
+impl PackedBoard {
+	/// Packs a board, reusing the words from the last board packed
+	pub fn pack(&mut self, board: &Board) {
+		self.words_per_row = (board.width + 2).div_ceil(64);
+		self.words.clear();
+		self.words
+			.resize(self.words_per_row * (board.height + 2), 0);
+
+		for padded_row in 0..board.height + 2 {
+			let row_words = &mut self.words
+				[padded_row * self.words_per_row..(padded_row + 1) * self.words_per_row];
+			let row = padded_row as isize - 1;
+			for padded_col in 0..board.width + 2 {
+				let col = padded_col as isize - 1;
+				let state = if (0..board.height as isize).contains(&row)
+					&& (0..board.width as isize).contains(&col)
+				{
+					board[row as usize * board.width + col as usize].state
+				} else {
+					board.neighbor_state(0, 0, row, col)
+				};
+				row_words[padded_col / 64] |= ((state & 0b01) as u64) << (padded_col % 64);
+			}
+		}
+	}
+
+	pub fn words_per_row(&self) -> usize {
+		self.words_per_row
+	}
+
+	/// A word of a row of the board. Bit `i` of word `w` is the cell in
+	/// column `64 * w + i - 1`, since the row starts with its border cell.
+	pub fn word(&self, row: usize, word: usize) -> u64 {
+		self.words[(row + 1) * self.words_per_row + word]
+	}
+
+	/// Counts the live neighbors of each cell in a word of a row, with a
+	/// ripple of half adders for each of the eight neighbors
+	pub fn count_neighbors(&self, row: usize, word: usize) -> Counts {
+		let mut counts = [0; 4];
+		for padded_row in row..row + 3 {
+			let words = &self.words[padded_row * self.words_per_row..][..self.words_per_row];
+			let center = words[word];
+			let before = if word > 0 { words[word - 1] } else { 0 };
+			let after = words.get(word + 1).copied().unwrap_or(0);
+
+			// The neighbor to the left of bit `i` is bit `i - 1`
+			add(&mut counts, center << 1 | before >> 63);
+			add(&mut counts, center >> 1 | after << 63);
+			if padded_row != row + 1 {
+				add(&mut counts, center);
+			}
+		}
+		counts
+	}
+}
+
+/// Adds one bit to each of the 64 counts
+fn add(counts: &mut Counts, mut carry: u64) {
+	for bit in counts.iter_mut() {
+		let sum = *bit ^ carry;
+		carry &= *bit;
+		*bit = sum;
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::PackedBoard;
+	use crate::rules::life_like::count_live_neighbors;
+	use crate::rules::{LifeLike, Visibility};
+	use crate::world::{mix, Board, Boundary, Cell};
+
+	#[test]
+	fn packed_counts_match_cell_counts() {
+		for boundary in [
+			Boundary::Dead,
+			Boundary::Alive,
+			Boundary::Torus,
+			Boundary::Mirror,
+		] {
+			let (width, height) = (70, 5);
+			let board = Board {
+				cells: (0..width * height)
+					.map(|idx| Cell {
+						state: (mix(2, 0, idx / width, idx % width) % 4) as u8,
+						..Default::default()
+					})
+					.collect(),
+				width,
+				height,
+				generation: 0,
+				boundary,
+			};
+			let mut packed = PackedBoard::default();
+			packed.pack(&board);
+
+			let rule = LifeLike::HIGHLIFE;
+			for row in 0..height {
+				for word in 0..packed.words_per_row() {
+					let counts = packed.count_neighbors(row, word);
+					let alive = packed.word(row, word);
+					let next = rule.next_alive_packed(alive, &counts);
+					for bit in 0..64 {
+						let col = (word * 64 + bit).wrapping_sub(1);
+						if col >= width {
+							continue;
+						}
+						let count = (0..4)
+							.map(|k| ((counts[k] >> bit & 1) << k) as u32)
+							.sum::<u32>();
+						assert_eq!(
+							count,
+							count_live_neighbors(&board, row, col, Visibility::ALL),
+							"{boundary:?} ({row}, {col})"
+						);
+						assert_eq!(
+							(next >> bit & 1) as u8 | ((alive >> bit & 1) as u8) << 1,
+							rule.next_state(&board, row, col, Visibility::ALL)
+						);
+					}
+				}
+			}
+		}
+	}
+}
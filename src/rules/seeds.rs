@@ -1,62 +1,49 @@
-use crate::rules::Ruleset;
-use crate::world::{Board, Cell};
+use crate::rules::{LifeLike, Rule, Visibility};
+use crate::world::Board;
 use nannou::color::LinSrgba;
 use nannou::rand;
 use std::fmt::Write;
 
+#[derive(Debug)]
 pub struct Seeds;
 impl Seeds {
-	pub fn alive() -> Cell {
-		Cell {
-			ruleset: Ruleset::Seeds,
-			state: State::Alive as u8,
-		}
+	pub const RULE: LifeLike = LifeLike::new(&[2], &[]);
+}
+
+impl Rule for Seeds {
+	fn on(&self) -> u8 {
+		State::Alive as u8
 	}
 
-	pub fn dead() -> Cell {
-		Cell {
-			ruleset: Ruleset::Seeds,
-			state: State::Dead as u8,
-		}
+	fn off(&self) -> u8 {
+		State::Dead as u8
 	}
 
-	pub fn random() -> Cell {
-		Cell {
-			ruleset: Ruleset::Seeds,
-			state: rand::random_range(0, 2),
-		}
+	fn random(&self) -> u8 {
+		rand::random_range(0, 2)
+	}
+
+	fn life_like(&self) -> Option<LifeLike> {
+		Some(Seeds::RULE)
 	}
 
-	fn state(cell: Cell) -> State {
-		if cell.state >= State::Alive as u8 {
-			State::Alive
+	fn color(&self, state: u8) -> LinSrgba {
+		if state & 0b01 == State::Alive as u8 {
+			LinSrgba::new(0.0, 1.0, 0.5, 1.0)
 		} else {
-			State::Dead
+			LinSrgba::new(0.0, 0.0, 0.0, 1.0)
 		}
 	}
 
-	pub fn color(cell: Cell) -> LinSrgba {
-		match Seeds::state(cell) {
-			State::Alive => LinSrgba::new(0.0, 1.0, 0.5, 1.0),
-			State::Dead => LinSrgba::new(0.0, 0.0, 0.0, 1.0),
-		}
+	fn rule_color(&self) -> LinSrgba {
+		LinSrgba::new(0.0, 1.0, 0.5, 0.125)
 	}
 
-	pub fn next_cell_state(board: &Board, row: usize, col: usize) -> Cell {
-		let idx = row * board.width + col;
-		if board[idx].state == State::Alive as u8 {
-			Seeds::dead()
-		} else {
-			let live_neighbors = count_live_neighbors(board, row, col);
-			if live_neighbors == 2 {
-				Seeds::alive()
-			} else {
-				Seeds::dead()
-			}
-		}
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		Seeds::RULE.next_state(board, row, col, visibility)
 	}
 
-	pub fn write_debug<W: Write>(output: &mut W, state: u8) -> std::fmt::Result {
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
 		write!(output, "{:02b}", state)
 	}
 }
@@ -67,41 +54,3 @@ enum State {
 	Dead = 0,
 	Alive = 1,
 }
-
-fn count_live_row_neighbors(board: &Board, row: usize, col: usize, exclude_center: bool) -> u8 {
-	let idx = row * board.width + col;
-
-	let mut live = if exclude_center {
-		0
-	} else {
-		board[idx].state & 0b01
-	};
-
-	if col > 0 {
-		live <<= 1;
-		live |= board[idx - 1].state & 0b01;
-	}
-
-	if (col + 1) < board.width {
-		live <<= 1;
-		live |= board[idx + 1].state & 0b01;
-	}
-
-	live
-}
-
-fn count_live_neighbors(board: &Board, row: usize, col: usize) -> u32 {
-	let mut live_neighbors = count_live_row_neighbors(board, row, col, true);
-
-	if row > 0 {
-		live_neighbors <<= 3;
-		live_neighbors |= count_live_row_neighbors(board, row - 1, col, false);
-	}
-
-	if row + 1 < board.height {
-		live_neighbors <<= 3;
-		live_neighbors |= count_live_row_neighbors(board, row + 1, col, false);
-	}
-
-	live_neighbors.count_ones()
-}
@@ -0,0 +1,135 @@
+use crate::rules::life_like::count_live_neighbors;
+use crate::rules::{Rule, Visibility, Vitality};
+use crate::world::Board;
+use nannou::color::LinSrgba;
+use nannou::rand;
+use std::fmt::Write;
+
+/// Wireworld: electrons travel along conductors that are drawn onto the
+/// board. Only electron heads are live; conductors and electron tails are
+/// kept out of the lowest bit so they don't count as live neighbors.
+#[derive(Debug)]
+pub struct Wireworld;
+
+impl Rule for Wireworld {
+	fn on(&self) -> u8 {
+		CONDUCTOR
+	}
+
+	fn off(&self) -> u8 {
+		EMPTY
+	}
+
+	fn random(&self) -> u8 {
+		[EMPTY, CONDUCTOR, HEAD, TAIL][rand::random_range(0, 4)]
+	}
+
+	/// The first stroke over an empty cell lays a conductor, and a second
+	/// stroke over a conductor starts an electron.
+	fn paint(&self, state: u8, first_touch: bool) -> u8 {
+		if !first_touch {
+			return state;
+		}
+		match state {
+			EMPTY => CONDUCTOR,
+			CONDUCTOR | TAIL => HEAD,
+			_ => state,
+		}
+	}
+
+	/// Conductors are part of the circuit, not a history of life
+	fn export(&self, state: u8) -> Vitality {
+		match state {
+			HEAD => Vitality::Alive,
+			TAIL => Vitality::WasAlive,
+			_ => Vitality::Dead,
+		}
+	}
+
+	fn import(&self, vitality: Vitality) -> u8 {
+		match vitality {
+			Vitality::Alive => HEAD,
+			Vitality::WasAlive => TAIL,
+			Vitality::Dead => EMPTY,
+		}
+	}
+
+	fn color(&self, state: u8) -> LinSrgba {
+		match state {
+			CONDUCTOR => LinSrgba::new(1.0, 0.6, 0.0, 1.0),
+			HEAD => LinSrgba::new(0.2, 0.5, 1.0, 1.0),
+			TAIL => LinSrgba::new(1.0, 0.2, 0.1, 1.0),
+			_ => LinSrgba::new(0.0, 0.0, 0.0, 1.0),
+		}
+	}
+
+	fn rule_color(&self) -> LinSrgba {
+		LinSrgba::new(0.6, 0.3, 0.1, 0.125)
+	}
+
+	fn next_cell_state(&self, board: &Board, row: usize, col: usize, visibility: Visibility) -> u8 {
+		match board[row * board.width + col].state {
+			HEAD => TAIL,
+			TAIL => CONDUCTOR,
+			CONDUCTOR => match count_live_neighbors(board, row, col, visibility) {
+				1 | 2 => HEAD,
+				_ => CONDUCTOR,
+			},
+			_ => EMPTY,
+		}
+	}
+
+	fn write_debug(&self, output: &mut dyn Write, state: u8) -> std::fmt::Result {
+		let name = match state {
+			EMPTY => "empty",
+			CONDUCTOR => "conductor",
+			HEAD => "head",
+			TAIL => "tail",
+			_ => "?",
+		};
+		write!(output, "{}", name)
+	}
+}
+
+const EMPTY: u8 = 0b000;
+const HEAD: u8 = 0b001;
+const TAIL: u8 = 0b010;
+const CONDUCTOR: u8 = 0b100;
+
+#[cfg(test)]
+mod test {
+	use super::{Wireworld, CONDUCTOR, EMPTY, HEAD, TAIL};
+	use crate::rules::{Rule, Visibility};
+	use crate::world::{Board, Boundary, Cell};
+
+	#[test]
+	fn electrons_travel_along_conductors() {
+		let mut board = Board {
+			cells: [HEAD, CONDUCTOR, CONDUCTOR, EMPTY]
+				.into_iter()
+				.map(|state| Cell {
+					state,
+					..Default::default()
+				})
+				.collect(),
+			width: 4,
+			height: 1,
+			generation: 0,
+			boundary: Boundary::Dead,
+		};
+
+		let mut step = || {
+			let next = (0..4)
+				.map(|col| Wireworld.next_cell_state(&board, 0, col, Visibility::ALL))
+				.collect::<Vec<_>>();
+			for (cell, state) in board.cells.iter_mut().zip(&next) {
+				cell.state = *state;
+			}
+			next
+		};
+
+		assert_eq!(step(), vec![TAIL, HEAD, CONDUCTOR, EMPTY]);
+		assert_eq!(step(), vec![CONDUCTOR, TAIL, HEAD, EMPTY]);
+		assert_eq!(step(), vec![CONDUCTOR, CONDUCTOR, TAIL, EMPTY]);
+	}
+}
@@ -0,0 +1,49 @@
+/// The cells a brush stroke has passed over, so that painting can tell a
+/// cell's first touch in a stroke from the rest
+#[derive(Debug, Default)]
+pub struct Stroke {
+	touched: Vec<bool>,
+	/// Whether anything has been touched since the last stroke ended
+	painting: bool,
+}
+
+impl Stroke {
+	pub fn new(cells: usize) -> Stroke {
+		Stroke {
+			touched: vec![false; cells],
+			painting: false,
+		}
+	}
+
+	/// Notes that the stroke passed over a cell, and returns whether that's
+	/// the first time it has
+	pub fn touch(&mut self, idx: usize) -> bool {
+		self.painting = true;
+		!std::mem::replace(&mut self.touched[idx], true)
+	}
+
+	/// Ends the stroke. Frames without one don't have anything to clear.
+	pub fn end(&mut self) {
+		if self.painting {
+			self.touched.fill(false);
+			self.painting = false;
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::Stroke;
+
+	#[test]
+	fn cells_are_touched_once_a_stroke() {
+		let mut stroke = Stroke::new(4);
+		assert!(stroke.touch(2));
+		assert!(!stroke.touch(2));
+		assert!(stroke.touch(3));
+
+		stroke.end();
+		assert!(stroke.touch(2));
+		assert!(!stroke.touch(2));
+	}
+}
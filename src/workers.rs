@@ -0,0 +1,238 @@
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+/// Threads kept around between generations to work out bands of rows, so a
+/// generation hands them work instead of starting threads of its own.
+/// Threads are only started the first time a job needs them.
+pub struct Workers {
+	shared: Arc<Shared>,
+	threads: Vec<JoinHandle<()>>,
+}
+
+struct Shared {
+	state: Mutex<State>,
+	/// Wakes the workers when there's a new job
+	job_posted: Condvar,
+	/// Wakes whoever posted the job when the last worker is done with it
+	job_done: Condvar,
+}
+
+struct State {
+	job: Option<Job>,
+	/// How many parts the job is split into. Part 0 is run by whoever posted
+	/// it, and part `n` by the `n - 1`th worker
+	parts: usize,
+	/// Counts the jobs posted, so a worker can tell a new one from the one
+	/// it just finished
+	round: u64,
+	/// Workers still running their part of the job
+	running: usize,
+	panicked: bool,
+	shutting_down: bool,
+}
+
+/// The job being run, with its lifetime erased so the workers can hold it.
+/// It's only ever called while `Workers::run` is waiting for it to finish.
+#[derive(Copy, Clone)]
+struct Job(*const (dyn Fn(usize) + Sync));
+
+unsafe impl Send for Job {}
+
+impl Workers {
+	pub fn new() -> Workers {
+		Workers {
+			shared: Arc::new(Shared {
+				state: Mutex::new(State {
+					job: None,
+					parts: 0,
+					round: 0,
+					running: 0,
+					panicked: false,
+					shutting_down: false,
+				}),
+				job_posted: Condvar::new(),
+				job_done: Condvar::new(),
+			}),
+			threads: Vec::new(),
+		}
+	}
+
+	/// Runs `job` once for every part from `0` to `parts`, each part on its
+	/// own thread, and returns once all of them are done
+	pub fn run(&mut self, parts: usize, job: &(dyn Fn(usize) + Sync)) {
+		if parts <= 1 {
+			(0..parts).for_each(job);
+			return;
+		}
+		while self.threads.len() < parts - 1 {
+			let shared = Arc::clone(&self.shared);
+			let worker = self.threads.len();
+			self.threads.push(
+				thread::Builder::new()
+					.name(format!("band {}", worker + 1))
+					.spawn(move || shared.work(worker))
+					.expect("couldn't start a band worker"),
+			);
+		}
+
+		// SAFETY: the job outlives its use, since nothing returns from here
+		// until every worker has finished its part and the job is taken back
+		let job = Job(unsafe {
+			std::mem::transmute::<*const (dyn Fn(usize) + Sync + '_), *const (dyn Fn(usize) + Sync)>(
+				job,
+			)
+		});
+		{
+			let mut state = self.shared.lock();
+			state.job = Some(job);
+			state.parts = parts;
+			state.round += 1;
+			state.running = parts - 1;
+		}
+		self.shared.job_posted.notify_all();
+
+		// Even if this part panics, the workers are still using the job
+		let own_part = panic::catch_unwind(AssertUnwindSafe(|| unsafe { (*job.0)(0) }));
+		let mut state = self.shared.lock();
+		while state.running > 0 {
+			state = self.shared.job_done.wait(state).unwrap();
+		}
+		state.job = None;
+		let worker_panicked = std::mem::replace(&mut state.panicked, false);
+		drop(state);
+
+		if let Err(payload) = own_part {
+			panic::resume_unwind(payload);
+		}
+		if worker_panicked {
+			panic!("a band worker panicked");
+		}
+	}
+}
+
+impl Shared {
+	fn lock(&self) -> MutexGuard<'_, State> {
+		self.state
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	fn work(&self, worker: usize) {
+		let mut round = 0;
+		loop {
+			let mut state = self.lock();
+			while state.round == round && !state.shutting_down {
+				state = self.job_posted.wait(state).unwrap();
+			}
+			if state.shutting_down {
+				return;
+			}
+			round = state.round;
+			let part = worker + 1;
+			let job = match state.job {
+				Some(job) if part < state.parts => job,
+				_ => continue,
+			};
+			drop(state);
+
+			// SAFETY: `Workers::run` doesn't return until `running` drops to 0
+			let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe { (*job.0)(part) }));
+			let mut state = self.lock();
+			state.panicked |= result.is_err();
+			state.running -= 1;
+			if state.running == 0 {
+				self.job_done.notify_one();
+			}
+		}
+	}
+}
+
+impl Default for Workers {
+	fn default() -> Self {
+		Workers::new()
+	}
+}
+
+impl Drop for Workers {
+	fn drop(&mut self) {
+		self.shared.lock().shutting_down = true;
+		self.shared.job_posted.notify_all();
+		for thread in self.threads.drain(..) {
+			let _ = thread.join();
+		}
+	}
+}
+
+impl std::fmt::Debug for Workers {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Workers")
+			.field("threads", &self.threads.len())
+			.finish()
+	}
+}
+
+/// A slice split into chunks that the parts of a job can each borrow
+/// mutably, as long as no two parts borrow the same chunk
+pub struct Chunks<'a, T> {
+	ptr: *mut T,
+	len: usize,
+	chunk_size: usize,
+	_slice: PhantomData<&'a mut [T]>,
+}
+
+unsafe impl<T: Send> Sync for Chunks<'_, T> {}
+
+impl<'a, T> Chunks<'a, T> {
+	pub fn new(slice: &'a mut [T], chunk_size: usize) -> Chunks<'a, T> {
+		Chunks {
+			ptr: slice.as_mut_ptr(),
+			len: slice.len(),
+			chunk_size: chunk_size.max(1),
+			_slice: PhantomData,
+		}
+	}
+
+	/// The `n`th chunk, which is shorter at the end of the slice
+	///
+	/// # Safety
+	///
+	/// Nothing else can be borrowing the same chunk.
+	#[allow(clippy::mut_from_ref)]
+	pub unsafe fn get(&self, n: usize) -> &'a mut [T] {
+		let start = (n * self.chunk_size).min(self.len);
+		let end = (start + self.chunk_size).min(self.len);
+		std::slice::from_raw_parts_mut(self.ptr.add(start), end - start)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn every_part_runs_once_on_its_own_chunk() {
+		let mut workers = Workers::new();
+		let mut numbers = vec![0; 10];
+		for round in 1..=3 {
+			let chunks = Chunks::new(&mut numbers, 3);
+			workers.run(4, &|part| {
+				for number in unsafe { chunks.get(part) } {
+					*number += round * (part + 1);
+				}
+			});
+		}
+		assert_eq!(numbers, [6, 6, 6, 12, 12, 12, 18, 18, 18, 24]);
+		assert_eq!(workers.threads.len(), 3);
+
+		// Fewer parts leave the rest of the workers waiting
+		let chunks = Chunks::new(&mut numbers, 5);
+		workers.run(2, &|part| {
+			for number in unsafe { chunks.get(part) } {
+				*number = part;
+			}
+		});
+		assert_eq!(numbers, [0, 0, 0, 0, 0, 1, 1, 1, 1, 1]);
+	}
+}
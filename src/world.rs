@@ -1,6 +1,11 @@
-use crate::rules::Ruleset;
+use crate::activity::Activity;
+use crate::energy::Energy;
+use crate::equilibrium::{EquilibriumDetector, Scope};
+use crate::noise::Noise;
+use crate::rules::{LifeLike, Margolus, PackedBoard, Registry, Ruleset};
+use crate::workers::{Chunks, Workers};
 
-#[derive(Copy, Clone, Default, Debug)]
+#[derive(Copy, Clone, Default, Debug, Hash, PartialEq, Eq)]
 pub struct Cell {
 	pub ruleset: Ruleset,
 	pub state: u8,
@@ -8,15 +13,170 @@ pub struct Cell {
 
 #[derive(Debug)]
 struct Growth {
+	strategy: GrowthStrategy,
+	claims: TerritoryClaims,
 	all_live_neighboring_rulests: Vec<Ruleset>,
 	deduped_live_neighboring_rulesets: Vec<Ruleset>,
 	possible_next_cells: Vec<Cell>,
 	live_rulesets_by_population: Vec<(Ruleset, u8)>,
 }
 
+/// How a cell contested by several rulesets picks the one that takes it
+/// over. Only rulesets whose rule would leave the cell alive can take it, and
+/// remaining ties go to the ruleset with the lowest id, so that runs can be
+/// reproduced.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum GrowthStrategy {
+	/// The ruleset with the most live neighbors wins
+	#[default]
+	Plurality,
+	/// A ruleset wins with a chance proportional to its live neighbors. The
+	/// dice are a hash of the seed, generation and position, so the same seed
+	/// plays out the same way every time.
+	WeightedRandom { seed: u64 },
+	/// Rulesets earlier in the list beat later ones, and listed rulesets beat
+	/// unlisted ones. Otherwise it comes down to plurality.
+	Dominance(Vec<Ruleset>),
+	/// Each ruleset beats the one after it, and the last one beats the first.
+	/// The ruleset that beats the most of the others around the cell wins,
+	/// then plurality.
+	RockPaperScissors(Vec<Ruleset>),
+	/// Live neighbors count for as much as their ruleset's strength, indexed
+	/// by ruleset id. Rulesets without one have a strength of 1.
+	Strength(Vec<f32>),
+}
+
+/// Which cells around a cell stake a claim to it for their ruleset
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum TerritoryClaims {
+	/// Only live cells
+	#[default]
+	Live,
+	/// Every cell its rule considers present, like refractory cells in
+	/// Brian's Brain or cells with a history of having been alive
+	Present,
+}
+
+/// Lets abandoned territory dissolve back into a background ruleset. A cell
+/// reverts once it has gone `generations` generations without a live cell
+/// of its own ruleset in it or around it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TerritoryDecay {
+	pub background: Ruleset,
+	pub generations: u16,
+}
+
+/// When the cells of a ruleset take their next state within a generation
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum UpdateMode {
+	/// Every cell at once, from the board as it was at the start of the
+	/// generation
+	#[default]
+	Synchronous,
+	/// One cell at a time in a random order, each seeing the new states of
+	/// the cells updated before it
+	RandomSequential { seed: u64 },
+	/// Each cell updates with a chance of `alpha`, and otherwise stays as it
+	/// is for another generation
+	AlphaAsynchronous { alpha: f32, seed: u64 },
+	/// The black squares of a checkerboard update first, then the white
+	/// squares, seeing the new states of the black ones
+	Checkerboard,
+}
+
+/// The update mode of every ruleset, with a default for the rulesets that
+/// don't have their own
+#[derive(Clone, Debug, Default)]
+struct UpdateModes {
+	global: UpdateMode,
+	rulesets: Vec<Option<UpdateMode>>,
+}
+
+impl UpdateModes {
+	fn of(&self, ruleset: Ruleset) -> UpdateMode {
+		self.rulesets
+			.get(ruleset.index())
+			.copied()
+			.flatten()
+			.unwrap_or(self.global)
+	}
+}
+
+/// How a board's cells are carried over when it changes size
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Fit {
+	/// Cells stay where they are, losing whatever falls off the bottom and
+	/// right edges, and new cells start out blank
+	#[default]
+	Crop,
+	/// The board is stretched or squeezed into the new size, every cell
+	/// taking the state of the nearest one on the old board
+	Scale,
+}
+
+impl Fit {
+	/// Where a cell of a board resized from `from` to `to` comes from on the
+	/// old board, if anywhere. Sizes are `(width, height)`.
+	fn source(
+		self,
+		row: usize,
+		col: usize,
+		from: (usize, usize),
+		to: (usize, usize),
+	) -> Option<usize> {
+		let (row, col) = match self {
+			Fit::Crop => (row, col),
+			Fit::Scale => (row * from.1 / to.1, col * from.0 / to.0),
+		};
+		(row < from.1 && col < from.0).then_some(row * from.0 + col)
+	}
+
+	/// Where a cell of the old board ends up on the resized board, if it
+	/// survives the resize
+	fn destination(self, idx: usize, from: (usize, usize), to: (usize, usize)) -> Option<usize> {
+		let (row, col) = (idx / from.0, idx % from.0);
+		let (row, col) = match self {
+			Fit::Crop => (row, col),
+			Fit::Scale => (row * to.1 / from.1, col * to.0 / from.0),
+		};
+		(row < to.1 && col < to.0).then_some(row * to.0 + col)
+	}
+
+	/// Carries a grid of values over to the new size, filling in with
+	/// `blank` where there's nothing to carry over
+	pub(crate) fn resample<T: Copy>(
+		self,
+		values: &[T],
+		from: (usize, usize),
+		to: (usize, usize),
+		blank: T,
+	) -> Vec<T> {
+		(0..to.0 * to.1)
+			.map(|idx| {
+				self.source(idx / to.0, idx % to.0, from, to)
+					.map_or(blank, |source| values[source])
+			})
+			.collect()
+	}
+}
+
+impl std::fmt::Display for GrowthStrategy {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			GrowthStrategy::Plurality => write!(fmt, "plurality"),
+			GrowthStrategy::WeightedRandom { seed } => write!(fmt, "weighted random ({seed})"),
+			GrowthStrategy::Dominance(_) => write!(fmt, "dominance"),
+			GrowthStrategy::RockPaperScissors(_) => write!(fmt, "rock paper scissors"),
+			GrowthStrategy::Strength(_) => write!(fmt, "strength"),
+		}
+	}
+}
+
 impl Default for Growth {
 	fn default() -> Self {
 		Growth {
+			strategy: GrowthStrategy::default(),
+			claims: TerritoryClaims::default(),
 			all_live_neighboring_rulests: Vec::with_capacity(9),
 			deduped_live_neighboring_rulesets: Vec::with_capacity(9),
 			possible_next_cells: Vec::with_capacity(9),
@@ -26,12 +186,18 @@ impl Default for Growth {
 }
 
 impl Growth {
-	fn find_neighboring_rulesets(&mut self, board: &Board, row: usize, col: usize) {
-		adjacent_live_rulesets(&mut self.all_live_neighboring_rulests, board, row, col);
-		self.deduped_live_neighboring_rulesets.clear();
-		self.deduped_live_neighboring_rulesets
-			.extend_from_slice(&self.all_live_neighboring_rulests);
-		self.deduped_live_neighboring_rulesets.dedup();
+	/// Finds the rulesets competing for a cell, and which of them would
+	/// leave it alive. Each rule sees the contested cell translated into one
+	/// of its own, and the board is put back the way it was afterwards.
+	fn find_neighboring_rulesets(
+		&mut self,
+		rules: &Registry,
+		board: &mut Board,
+		row: usize,
+		col: usize,
+		energy: Option<f32>,
+	) {
+		self.collect_neighboring_rulesets(rules, board, row, col);
 
 		if self.has_competing_rulesets() {
 			sort_rulesets_by_population(
@@ -41,8 +207,12 @@ impl Growth {
 
 			self.possible_next_cells.clear();
 
+			let idx = row * board.width + col;
+			let cell = board[idx];
 			for ruleset in &self.deduped_live_neighboring_rulesets {
-				let possible_next = ruleset.next_cell_state(board, row, col);
+				board[idx] = rules.translate(cell, *ruleset);
+				let possible_next = rules.next_cell_state(*ruleset, board, row, col, energy);
+				board[idx] = cell;
 				if possible_next.state & 0b01 > 0 {
 					self.possible_next_cells.push(possible_next);
 				}
@@ -50,27 +220,197 @@ impl Growth {
 		}
 	}
 
+	/// Finds the rulesets that stake a claim to a cell, without trying any
+	/// of them out
+	fn collect_neighboring_rulesets(
+		&mut self,
+		rules: &Registry,
+		board: &Board,
+		row: usize,
+		col: usize,
+	) {
+		match self.claims {
+			TerritoryClaims::Live => adjacent_live_rulesets(
+				&mut self.all_live_neighboring_rulests,
+				board,
+				row,
+				col,
+				is_live,
+			),
+			TerritoryClaims::Present => adjacent_live_rulesets(
+				&mut self.all_live_neighboring_rulests,
+				board,
+				row,
+				col,
+				|cell| rules.is_present(cell),
+			),
+		}
+		self.deduped_live_neighboring_rulesets.clear();
+		self.deduped_live_neighboring_rulesets
+			.extend_from_slice(&self.all_live_neighboring_rulests);
+		self.deduped_live_neighboring_rulesets.dedup();
+	}
+
 	fn has_competing_rulesets(&self) -> bool {
 		self.deduped_live_neighboring_rulesets.len() > 1
 	}
 
-	fn next_live_state(&self) -> Option<Cell> {
+	fn population(&self, ruleset: Ruleset) -> u8 {
 		self.live_rulesets_by_population
 			.iter()
-			.find_map(|(ruleset, _)| {
-				self.possible_next_cells
+			.find(|(r, _)| *r == ruleset)
+			.map_or(0, |(_, count)| *count)
+	}
+
+	/// How strongly a ruleset claims the contested cell. Equal scores fall
+	/// back to population, then to the lowest id.
+	fn score(&self, ruleset: Ruleset) -> f32 {
+		match &self.strategy {
+			GrowthStrategy::Plurality | GrowthStrategy::WeightedRandom { .. } => {
+				self.population(ruleset) as f32
+			}
+			GrowthStrategy::Dominance(hierarchy) => hierarchy
+				.iter()
+				.position(|r| *r == ruleset)
+				.map_or(0.0, |rank| (hierarchy.len() - rank) as f32),
+			GrowthStrategy::RockPaperScissors(cycle) => {
+				let beats = |a: Ruleset, b: Ruleset| {
+					cycle
+						.iter()
+						.position(|r| *r == a)
+						.is_some_and(|idx| cycle[(idx + 1) % cycle.len()] == b && a != b)
+				};
+				self.deduped_live_neighboring_rulesets
 					.iter()
-					.find(|next_cell| next_cell.ruleset == *ruleset)
+					.map(|&other| beats(ruleset, other) as i32 - beats(other, ruleset) as i32)
+					.sum::<i32>() as f32
+			}
+			GrowthStrategy::Strength(strengths) => {
+				let strength = strengths.get(ruleset.index()).copied().unwrap_or(1.0);
+				self.population(ruleset) as f32 * strength
+			}
+		}
+	}
+
+	fn next_live_state(&self, generation: u64, row: usize, col: usize) -> Option<Cell> {
+		if let GrowthStrategy::WeightedRandom { seed } = self.strategy {
+			let total = self
+				.possible_next_cells
+				.iter()
+				.map(|cell| self.population(cell.ruleset) as u64)
+				.sum::<u64>();
+			if total == 0 {
+				return None;
+			}
+			let mut roll = mix(seed, generation, row, col) % total;
+			return self
+				.possible_next_cells
+				.iter()
+				.find(|cell| {
+					let population = self.population(cell.ruleset) as u64;
+					if roll < population {
+						true
+					} else {
+						roll -= population;
+						false
+					}
+				})
+				.copied();
+		}
+
+		self.possible_next_cells
+			.iter()
+			.max_by(|a, b| {
+				self.score(a.ruleset)
+					.total_cmp(&self.score(b.ruleset))
+					.then(self.population(a.ruleset).cmp(&self.population(b.ruleset)))
+					.then(b.ruleset.cmp(&a.ruleset))
 			})
 			.copied()
 	}
 }
 
-#[derive(Debug, Clone)]
+/// Hashes a seed and a cell's position in space and time into a number that
+/// looks random, so random growth and noise can be replayed.
+pub(crate) fn mix(seed: u64, generation: u64, row: usize, col: usize) -> u64 {
+	let mut hash = seed;
+	for value in [generation, row as u64, col as u64] {
+		// splitmix64
+		hash = hash.wrapping_add(value).wrapping_add(0x9e3779b97f4a7c15);
+		hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+		hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+		hash ^= hash >> 31;
+	}
+	hash
+}
+
+/// Turns a hash into a number from 0 up to 1
+pub(crate) fn unit(hash: u64) -> f32 {
+	(hash >> 40) as f32 / (1u64 << 24) as f32
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Board {
 	pub cells: Vec<Cell>,
 	pub width: usize,
 	pub height: usize,
+	/// How many generations the world has run for. Rules that partition the
+	/// board into blocks use it to alternate the partition.
+	pub generation: u64,
+	pub boundary: Boundary,
+}
+
+/// What rules see past the edges of the board.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Boundary {
+	/// Cells off the edge are dead, or whatever a rule leaves as its quiet
+	/// background. Particles bounce off it like a wall.
+	#[default]
+	Dead,
+	/// Cells off the edge are alive. Particles bounce off it like a wall.
+	Alive,
+	/// The board wraps around, left to right and top to bottom, so it can be
+	/// tiled seamlessly.
+	Torus,
+	/// Cells off the edge mirror the cells along the edge, and particles
+	/// bounce back.
+	Mirror,
+}
+
+impl Boundary {
+	pub fn next(self) -> Self {
+		match self {
+			Boundary::Dead => Boundary::Alive,
+			Boundary::Alive => Boundary::Torus,
+			Boundary::Torus => Boundary::Mirror,
+			Boundary::Mirror => Boundary::Dead,
+		}
+	}
+
+	/// Whether particles moving off the edge of the board bounce back, for
+	/// rules that move particles around instead of counting neighbors. Only
+	/// a torus lets them leave, to come back on the other side.
+	pub fn reflects_particles(self) -> bool {
+		self != Boundary::Torus
+	}
+
+	fn resolve(self, pos: isize, len: usize) -> Option<usize> {
+		let len = len as isize;
+		if (0..len).contains(&pos) {
+			return Some(pos as usize);
+		}
+		match self {
+			Boundary::Dead | Boundary::Alive => None,
+			Boundary::Torus => Some(pos.rem_euclid(len) as usize),
+			Boundary::Mirror => {
+				// Reflect across the edge, so the first cell past the edge is
+				// the edge cell itself. Large neighborhoods on small boards
+				// can overshoot the far edge, so clamp what's left.
+				let reflected = if pos < 0 { -pos - 1 } else { 2 * len - pos - 1 };
+				Some(reflected.clamp(0, len - 1) as usize)
+			}
+		}
+	}
 }
 
 impl std::ops::Index<usize> for Board {
@@ -87,6 +427,15 @@ impl std::ops::IndexMut<usize> for Board {
 }
 
 impl Board {
+	/// Makes this board a copy of another, reusing its cells
+	pub fn copy_from(&mut self, other: &Board) {
+		self.cells.clone_from(&other.cells);
+		self.width = other.width;
+		self.height = other.height;
+		self.generation = other.generation;
+		self.boundary = other.boundary;
+	}
+
 	pub fn iter(&self) -> impl Iterator<Item=&Cell> {
 		self.cells.iter()
 	}
@@ -94,6 +443,49 @@ impl Board {
 	pub fn iter_mut(&mut self) -> impl Iterator<Item=&mut Cell> {
 		self.cells.iter_mut()
 	}
+
+	/// Whether any of a cell's 8 neighbors are off the edge of the board
+	pub fn is_edge(&self, row: usize, col: usize) -> bool {
+		row == 0 || col == 0 || row + 1 >= self.height || col + 1 >= self.width
+	}
+
+	/// Finds the index of the cell `dr` rows and `dc` columns away, following
+	/// the boundary past the edges of the board. Returns `None` for cells off
+	/// the edge of a dead or live boundary.
+	pub fn neighbor_idx(&self, row: usize, col: usize, dr: isize, dc: isize) -> Option<usize> {
+		let row = self.boundary.resolve(row as isize + dr, self.height)?;
+		let col = self.boundary.resolve(col as isize + dc, self.width)?;
+		Some(row * self.width + col)
+	}
+
+	/// Like `neighbor_idx`, except only a torus boundary leads anywhere past
+	/// the edges. Rules that move particles around use it, and bounce the
+	/// particles back themselves when the boundary reflects them.
+	pub fn wrapping_neighbor_idx(
+		&self,
+		row: usize,
+		col: usize,
+		dr: isize,
+		dc: isize,
+	) -> Option<usize> {
+		if self.boundary == Boundary::Torus {
+			self.neighbor_idx(row, col, dr, dc)
+		} else {
+			let row = Boundary::Dead.resolve(row as isize + dr, self.height)?;
+			let col = Boundary::Dead.resolve(col as isize + dc, self.width)?;
+			Some(row * self.width + col)
+		}
+	}
+
+	/// The state of the cell `dr` rows and `dc` columns away. Cells off the
+	/// edge of a dead boundary are `0b00`, and of a live boundary `0b01`.
+	pub fn neighbor_state(&self, row: usize, col: usize, dr: isize, dc: isize) -> u8 {
+		match self.neighbor_idx(row, col, dr, dc) {
+			Some(idx) => self.cells[idx].state,
+			None if self.boundary == Boundary::Alive => 0b01,
+			None => 0b00,
+		}
+	}
 }
 
 #[derive(Debug)]
@@ -101,9 +493,41 @@ pub struct World {
 	state_a: Board,
 	state_b: Board,
 	growth: Growth,
+	rules: Registry,
 	current_board: CurrentBoard,
-	pub temporary_rulesets: Vec<Option<Ruleset>>,
-	pub temporary_states: Vec<Option<u8>>,
+	decay: Option<TerritoryDecay>,
+	energy: Option<Energy>,
+	noise: Option<Noise>,
+	update_modes: UpdateModes,
+	/// How many threads to generate with
+	threads: usize,
+	/// The threads bands of rows are handed to, started the first time
+	/// they're needed and kept for the rest of the world's life
+	workers: Workers,
+	band_growths: Vec<Growth>,
+	/// The Life-like rule of each ruleset whose cells can be updated on the
+	/// packed board this generation
+	packed_rules: Vec<Option<LifeLike>>,
+	/// The block rule of each ruleset whose cells can be updated a whole
+	/// block at a time this generation
+	block_rules: Vec<Option<Margolus>>,
+	packed_board: PackedBoard,
+	activity: Activity,
+	equilibrium_detectors: Vec<EquilibriumDetector>,
+	/// Generations each cell has gone without a live cell of its own ruleset
+	/// nearby
+	abandoned_for: Vec<u16>,
+	/// The board rules see, with the temporary rulesets and states laid over
+	/// it, kept between generations along with the rest of the scratch space
+	scratch_board: Board,
+	/// Contested cells each band of rows leaves to be worked out afterwards
+	band_contested: Vec<Vec<usize>>,
+	white_squares: Vec<usize>,
+	sequential: Vec<(u64, usize)>,
+	temporary_rulesets: Vec<Option<Ruleset>>,
+	temporary_states: Vec<Option<u8>>,
+	/// Every cell with a temporary ruleset or state, which are usually few
+	overlaid: Vec<usize>,
 }
 
 impl World {
@@ -112,6 +536,8 @@ impl World {
 			cells: vec![Cell::default(); width * height],
 			width,
 			height,
+			generation: 0,
+			boundary: Boundary::default(),
 		};
 		let state_b = state_a.clone();
 		let temporary_rulesets = vec![None; width * height];
@@ -121,9 +547,28 @@ impl World {
 			state_a,
 			state_b,
 			growth: Default::default(),
+			rules: Default::default(),
 			current_board: CurrentBoard::A,
+			decay: None,
+			energy: None,
+			noise: None,
+			update_modes: UpdateModes::default(),
+			threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+			workers: Workers::new(),
+			band_growths: Vec::new(),
+			packed_rules: Vec::new(),
+			block_rules: Vec::new(),
+			packed_board: PackedBoard::default(),
+			activity: Activity::default(),
+			equilibrium_detectors: Vec::new(),
+			abandoned_for: vec![0; width * height],
+			scratch_board: Board::default(),
+			band_contested: Vec::new(),
+			white_squares: Vec::new(),
+			sequential: Vec::new(),
 			temporary_rulesets,
 			temporary_states,
+			overlaid: Vec::new(),
 		}
 	}
 
@@ -141,22 +586,198 @@ impl World {
 		}
 	}
 
-	pub fn this_board_and_next_and_temporary(
-		&mut self,
-	) -> (&mut Board, &mut Board, &[Option<Ruleset>], &[Option<u8>]) {
+	pub fn boundary(&self) -> Boundary {
+		self.state_a.boundary
+	}
+
+	pub fn set_boundary(&mut self, boundary: Boundary) {
+		self.state_a.boundary = boundary;
+		self.state_b.boundary = boundary;
+		self.activity.invalidate();
+	}
+
+	pub fn growth_strategy(&self) -> &GrowthStrategy {
+		&self.growth.strategy
+	}
+
+	pub fn set_growth_strategy(&mut self, strategy: GrowthStrategy) {
+		self.growth.strategy = strategy;
+		self.activity.invalidate();
+	}
+
+	pub fn territory_claims(&self) -> TerritoryClaims {
+		self.growth.claims
+	}
+
+	pub fn set_territory_claims(&mut self, claims: TerritoryClaims) {
+		self.growth.claims = claims;
+		self.activity.invalidate();
+	}
+
+	pub fn territory_decay(&self) -> Option<TerritoryDecay> {
+		self.decay
+	}
+
+	pub fn set_territory_decay(&mut self, decay: Option<TerritoryDecay>) {
+		self.decay = decay;
+		self.abandoned_for.fill(0);
+	}
+
+	pub fn energy(&self) -> Option<&Energy> {
+		self.energy.as_ref()
+	}
+
+	pub fn energy_mut(&mut self) -> Option<&mut Energy> {
+		self.energy.as_mut()
+	}
+
+	pub fn set_energy(&mut self, energy: Option<Energy>) {
+		self.energy = energy;
+	}
+
+	pub fn noise(&self) -> Option<&Noise> {
+		self.noise.as_ref()
+	}
+
+	pub fn noise_mut(&mut self) -> Option<&mut Noise> {
+		self.noise.as_mut()
+	}
+
+	pub fn set_noise(&mut self, noise: Option<Noise>) {
+		self.noise = noise;
+	}
+
+	pub fn threads(&self) -> usize {
+		self.threads
+	}
+
+	/// Sets how many threads to generate with. Boards are split into bands
+	/// of rows for them, unless cells update one at a time.
+	pub fn set_threads(&mut self, threads: usize) {
+		self.threads = threads.max(1);
+	}
+
+	pub fn update_mode(&self) -> UpdateMode {
+		self.update_modes.global
+	}
+
+	/// Sets the update mode of every ruleset that doesn't have its own
+	pub fn set_update_mode(&mut self, mode: UpdateMode) {
+		self.update_modes.global = mode;
+		self.activity.invalidate();
+	}
+
+	pub fn ruleset_update_mode(&self, ruleset: Ruleset) -> UpdateMode {
+		self.update_modes.of(ruleset)
+	}
+
+	/// Gives a ruleset its own update mode, or with `None` has it follow the
+	/// global one again
+	pub fn set_ruleset_update_mode(&mut self, ruleset: Ruleset, mode: Option<UpdateMode>) {
+		let rulesets = &mut self.update_modes.rulesets;
+		if rulesets.len() <= ruleset.index() {
+			rulesets.resize(ruleset.index() + 1, None);
+		}
+		rulesets[ruleset.index()] = mode;
+		self.activity.invalidate();
+	}
+
+	/// Starts watching part of the board for when it settles into a still
+	/// life or oscillator, looking back over `window` generations
+	pub fn watch_equilibrium(&mut self, scope: Scope, window: usize) {
+		self.equilibrium_detectors
+			.push(EquilibriumDetector::new(scope, window));
+	}
+
+	pub fn stop_watching_equilibrium(&mut self, scope: Scope) {
+		self.equilibrium_detectors
+			.retain(|detector| detector.scope() != scope);
+	}
+
+	pub fn equilibrium_detectors(&self) -> &[EquilibriumDetector] {
+		&self.equilibrium_detectors
+	}
+
+	pub fn rules(&self) -> &Registry {
+		&self.rules
+	}
+
+	pub fn rules_mut(&mut self) -> &mut Registry {
+		self.activity.invalidate();
+		&mut self.rules
+	}
+
+	/// Which tiles of the board were worked out in the last generation
+	pub fn activity(&self) -> &Activity {
+		&self.activity
+	}
+
+	pub fn activity_mut(&mut self) -> &mut Activity {
+		&mut self.activity
+	}
+
+	pub fn board_mut_and_rules(&mut self) -> (&mut Board, &Registry) {
 		match self.current_board {
-			CurrentBoard::A => (
-				&mut self.state_a,
-				&mut self.state_b,
-				&self.temporary_rulesets,
-				&self.temporary_states,
-			),
-			CurrentBoard::B => (
-				&mut self.state_b,
-				&mut self.state_a,
-				&self.temporary_rulesets,
-				&self.temporary_states,
-			),
+			CurrentBoard::A => (&mut self.state_a, &self.rules),
+			CurrentBoard::B => (&mut self.state_b, &self.rules),
+		}
+	}
+
+	pub fn temporary_rulesets(&self) -> &[Option<Ruleset>] {
+		&self.temporary_rulesets
+	}
+
+	pub fn temporary_states(&self) -> &[Option<u8>] {
+		&self.temporary_states
+	}
+
+	/// Lays a ruleset over a cell until it's cleared, as if the cell were
+	/// part of that ruleset's territory
+	pub fn set_temporary_ruleset(&mut self, idx: usize, ruleset: Ruleset) {
+		self.overlay(idx);
+		self.temporary_rulesets[idx] = Some(ruleset);
+	}
+
+	/// Holds a cell in a state until it's cleared
+	pub fn set_temporary_state(&mut self, idx: usize, state: u8) {
+		self.overlay(idx);
+		self.temporary_states[idx] = Some(state);
+	}
+
+	fn overlay(&mut self, idx: usize) {
+		if self.temporary_rulesets[idx].is_none() && self.temporary_states[idx].is_none() {
+			self.overlaid.push(idx);
+		}
+	}
+
+	pub fn clear_temporary_rulesets(&mut self) {
+		for &idx in &self.overlaid {
+			self.temporary_rulesets[idx] = None;
+		}
+		let states = &self.temporary_states;
+		self.overlaid.retain(|&idx| states[idx].is_some());
+	}
+
+	pub fn clear_temporary_states(&mut self) {
+		for &idx in &self.overlaid {
+			self.temporary_states[idx] = None;
+		}
+		let rulesets = &self.temporary_rulesets;
+		self.overlaid.retain(|&idx| rulesets[idx].is_some());
+	}
+
+	/// Paints the temporary rulesets onto both boards for good, translating
+	/// each cell for its new rule, and holds the temporary states for one
+	/// more generation
+	pub fn commit_temporary_rulesets(&mut self) {
+		for i in 0..self.overlaid.len() {
+			let idx = self.overlaid[i];
+			if let Some(ruleset) = self.temporary_rulesets[idx] {
+				self.set_ruleset(idx, ruleset);
+			}
+			if let Some(state) = self.temporary_states[idx] {
+				self.this_board_and_next().1[idx].state = state;
+			}
 		}
 	}
 
@@ -167,121 +788,342 @@ impl World {
 		}
 	}
 
-	fn boards_and_growth(
-		&mut self,
-	) -> (
-		&mut Board,
-		&mut Board,
-		&[Option<Ruleset>],
-		&[Option<u8>],
-		&mut Growth,
-	) {
-		match self.current_board {
-			CurrentBoard::A => (
-				&mut self.state_a,
-				&mut self.state_b,
-				&self.temporary_rulesets,
-				&self.temporary_states,
-				&mut self.growth,
-			),
-			CurrentBoard::B => (
-				&mut self.state_b,
-				&mut self.state_a,
-				&self.temporary_rulesets,
-				&self.temporary_states,
-				&mut self.growth,
-			),
+	fn generation(&mut self, growth_enabled: bool) -> Generation<'_> {
+		let (board, next_board) = match self.current_board {
+			CurrentBoard::A => (&mut self.state_a, &mut self.state_b),
+			CurrentBoard::B => (&mut self.state_b, &mut self.state_a),
+		};
+		// Plain two-state Life-like rules can be worked out 64 cells at a time,
+		// and block rules a block at a time, as long as nothing else has a say
+		// in what their cells become
+		self.packed_rules.clear();
+		self.block_rules.clear();
+		if !growth_enabled {
+			let (rules, update_modes) = (&self.rules, &self.update_modes);
+			let on_their_own = |ruleset| {
+				update_modes.of(ruleset) == UpdateMode::Synchronous && !rules.uses_energy(ruleset)
+			};
+			self.packed_rules.extend(rules.rulesets().map(|ruleset| {
+				rules.life_like(ruleset).filter(|_| {
+					on_their_own(ruleset) && rules.interactions().seen_by(ruleset).is_uniform()
+				})
+			}));
+			self.block_rules.extend(
+				rules
+					.rulesets()
+					.map(|ruleset| rules.margolus(ruleset).filter(|_| on_their_own(ruleset))),
+			);
+		}
+
+		Generation {
+			board,
+			next_board,
+			growth: &mut self.growth,
+			band_growths: &mut self.band_growths,
+			band_contested: &mut self.band_contested,
+			workers: &mut self.workers,
+			update_modes: &self.update_modes,
+			threads: self.threads,
+			packed_board: &mut self.packed_board,
+			step: Step {
+				rules: &self.rules,
+				temporary_rulesets: &self.temporary_rulesets,
+				temporary_states: &self.temporary_states,
+				packed_rules: &self.packed_rules,
+				block_rules: &self.block_rules,
+				activity: &self.activity,
+				energy: self.energy.as_ref().map(Energy::levels),
+				growth_enabled,
+			},
 		}
 	}
 
+	/// Hands a cell over to another ruleset on both boards, translating its
+	/// state so that it means the same thing to its new rule.
+	pub fn set_ruleset(&mut self, idx: usize, ruleset: Ruleset) {
+		self.state_a[idx] = self.rules.translate(self.state_a[idx], ruleset);
+		self.state_b[idx] = self.rules.translate(self.state_b[idx], ruleset);
+	}
+
 	pub fn randomize(&mut self) {
-		for cell in &mut self.board_mut().cells {
-			*cell = cell.ruleset.random();
+		let (board, rules) = self.board_mut_and_rules();
+		for cell in &mut board.cells {
+			*cell = rules.random(cell.ruleset);
+		}
+	}
+
+	/// Hands every cell on the board over to one ruleset
+	pub fn fill_ruleset(&mut self, ruleset: Ruleset) {
+		for cell in self.state_a.iter_mut().chain(self.state_b.iter_mut()) {
+			cell.ruleset = ruleset;
 		}
 	}
 
 	pub fn clear(&mut self) {
-		for cell in &mut self.board_mut().cells {
-			*cell = cell.ruleset.off();
+		let (board, rules) = self.board_mut_and_rules();
+		for cell in &mut board.cells {
+			*cell = rules.off(cell.ruleset);
 		}
 	}
 
 	pub fn reset(&mut self) {
-		let blank_cell = Ruleset::default().off();
+		let blank_cell = self.rules.off(Ruleset::default());
 		self.state_a.cells.fill(blank_cell);
 		self.state_b.cells.fill(blank_cell);
-		self.temporary_states.fill(None);
-		self.temporary_rulesets.fill(None);
+		self.state_a.generation = 0;
+		self.state_b.generation = 0;
+		self.clear_temporary_rulesets();
+		self.clear_temporary_states();
+		self.abandoned_for.fill(0);
+		if let Some(energy) = &mut self.energy {
+			energy.refill();
+		}
+		for detector in &mut self.equilibrium_detectors {
+			detector.reset();
+		}
 	}
 
+	/// Computes the next generation into the next board. Cells update in
+	/// passes: synchronous and alpha-asynchronous cells and the black squares
+	/// of checkerboard cells first, all from the board as it was; then the
+	/// white squares, seeing the black squares' new states; and last the
+	/// random-sequential cells, one at a time.
 	pub fn generate(&mut self, growth_enabled: bool) {
-		let (board, next_board, temporary_rulesets, temporary_states, growth) =
-			self.boards_and_growth();
-		// next_board
-		// 	.par_chunks_exact_mut(BOARD_WIDTH)
-		// 	.enumerate()
-		// 	.for_each(|(row, next_chunk)| {
-		// 		for col in 0..BOARD_WIDTH {
-		// 			let idx = row * BOARD_WIDTH + col;
-		// 			let cell = board[idx];
-		// 			let next_cell = cell.ruleset.next_cell_state(board, row, col);
-		// 			next_chunk[col].state = next_cell.state;
-		// 		}
-		// 	});
-
-		let scratch_cells = board
-			.iter()
-			.zip(temporary_states)
-			.zip(temporary_rulesets)
-			.map(|((cell, maybe_state), maybe_ruleset)| {
-				let state = maybe_state.unwrap_or(cell.state);
-				let ruleset = maybe_ruleset.unwrap_or(cell.ruleset);
-				Cell { ruleset, state }
-			})
-			.collect::<Vec<Cell>>();
-
-		let scratch_board = Board {
-			cells: scratch_cells,
-			width: board.width,
-			height: board.height,
+		let board = match self.current_board {
+			CurrentBoard::A => &self.state_a,
+			CurrentBoard::B => &self.state_b,
 		};
+		let (rules, temporary_rulesets, temporary_states) = (
+			&self.rules,
+			&self.temporary_rulesets,
+			&self.temporary_states,
+		);
+
+		// The scratch space is taken out of the world while the generation
+		// borrows the rest of it, and put back at the end
+		let mut scratch_board = std::mem::take(&mut self.scratch_board);
+		let mut white_squares = std::mem::take(&mut self.white_squares);
+		let mut sequential = std::mem::take(&mut self.sequential);
+		white_squares.clear();
+		sequential.clear();
+		scratch_board.copy_from(board);
+		for &idx in &self.overlaid {
+			let cell = match temporary_rulesets[idx] {
+				Some(ruleset) => rules.translate(board[idx], ruleset),
+				None => board[idx],
+			};
+			scratch_board[idx] = Cell {
+				ruleset: cell.ruleset,
+				state: temporary_states[idx].unwrap_or(cell.state),
+			};
+		}
 
-		for row in 0..board.height {
-			for col in 0..board.width {
-				if growth_enabled {
-					growth.find_neighboring_rulesets(&scratch_board, row, col);
+		// Cells that don't only depend on the cells around them have to be
+		// worked out every generation, along with the rest of their tile
+		let update_modes = &self.update_modes;
+		let rolls_dice =
+			growth_enabled && matches!(self.growth.strategy, GrowthStrategy::WeightedRandom { .. });
+		let has_energy = self.energy.is_some();
+		self.activity
+			.plan(&scratch_board, growth_enabled, rules.radius(), |idx| {
+				let ruleset = scratch_board[idx].ruleset;
+				rolls_dice
+					|| temporary_rulesets[idx].is_some()
+					|| temporary_states[idx].is_some()
+					|| update_modes.of(ruleset) != UpdateMode::Synchronous
+					|| rules.uses_generation(ruleset)
+					|| has_energy && rules.uses_energy(ruleset)
+			});
+
+		let mut generation = self.generation(growth_enabled);
+		generation.next_board.generation = generation.board.generation + 1;
+
+		let (width, height) = (scratch_board.width, scratch_board.height);
+		let bands = generation.threads.min(height / MIN_ROWS_PER_BAND).max(1);
+		generation.update_packed(&scratch_board);
+		generation.update_blocks(&scratch_board);
+		if bands > 1 && generation.is_simultaneous() {
+			generation.update_in_bands(&mut scratch_board, bands);
+		} else {
+			for row in 0..height {
+				for col in 0..width {
+					let idx = row * width + col;
+					if !generation.step.activity.is_active(row, col) {
+						generation.keep(idx);
+						continue;
+					}
+					if generation.step.worked_out_ahead(idx, generation.board[idx]) {
+						continue;
+					}
+					match generation.update_mode(idx) {
+						UpdateMode::Synchronous => generation.update(&mut scratch_board, row, col),
+						UpdateMode::AlphaAsynchronous { alpha, seed } => {
+							if unit(mix(seed, scratch_board.generation, row, col)) < alpha {
+								generation.update(&mut scratch_board, row, col)
+							} else {
+								generation.keep(idx)
+							}
+						}
+						UpdateMode::Checkerboard if (row + col) % 2 == 0 => {
+							generation.update(&mut scratch_board, row, col)
+						}
+						UpdateMode::Checkerboard => white_squares.push(idx),
+						UpdateMode::RandomSequential { seed } => {
+							sequential.push((mix(seed, scratch_board.generation, row, col), idx))
+						}
+					}
 				}
+			}
+		}
 
-				let idx = row * board.width + col;
-
-				if let Some(ruleset) = temporary_rulesets[idx] {
-					// If operating on a temporary ruleset, bypass growth. The shape of a person
-					// shouldn't grow.
-					let next_cell = ruleset.next_cell_state(&scratch_board, row, col);
-					next_board[idx].state = next_cell.state;
-				} else if growth_enabled && growth.has_competing_rulesets() {
-					// If growth is enabled and there's more than 1 live ruleset around a cell,
-					// compete for growth.
-					let next_cell = growth.next_live_state().unwrap_or_else(|| {
-						board[idx].ruleset.next_cell_state(&scratch_board, row, col)
-					});
-
-					if next_cell.ruleset != board[idx].ruleset {
-						board[idx].ruleset = next_cell.ruleset;
+		if !white_squares.is_empty() || !sequential.is_empty() {
+			for row in 0..height {
+				for col in 0..width {
+					let idx = row * width + col;
+					if generation.update_mode(idx) == UpdateMode::Checkerboard
+						&& (row + col) % 2 == 0
+					{
+						generation.publish(&mut scratch_board, idx);
 					}
+				}
+			}
+		}
+		for &idx in &white_squares {
+			generation.update(&mut scratch_board, idx / width, idx % width);
+		}
+		for &idx in &white_squares {
+			generation.publish(&mut scratch_board, idx);
+		}
+
+		sequential.sort_unstable();
+		for &(_, idx) in &sequential {
+			generation.update(&mut scratch_board, idx / width, idx % width);
+			generation.publish(&mut scratch_board, idx);
+		}
+
+		let next_board = match self.current_board {
+			CurrentBoard::A => &self.state_b,
+			CurrentBoard::B => &self.state_a,
+		};
+		self.activity.record(next_board);
+		self.scratch_board = scratch_board;
+		self.white_squares = white_squares;
+		self.sequential = sequential;
+
+		if let Some(energy) = &mut self.energy {
+			let next_board = match self.current_board {
+				CurrentBoard::A => &mut self.state_b,
+				CurrentBoard::B => &mut self.state_a,
+			};
+			energy.metabolize(&self.rules, next_board, &self.temporary_rulesets);
+		}
+
+		if let Some(noise) = &self.noise {
+			let next_board = match self.current_board {
+				CurrentBoard::A => &mut self.state_b,
+				CurrentBoard::B => &mut self.state_a,
+			};
+			noise.apply(&self.rules, next_board, &self.temporary_rulesets);
+		}
+
+		if let Some(decay) = self.decay {
+			self.decay_territory(decay);
+		}
+
+		let next_board = match self.current_board {
+			CurrentBoard::A => &self.state_b,
+			CurrentBoard::B => &self.state_a,
+		};
+		for detector in &mut self.equilibrium_detectors {
+			detector.observe(next_board);
+		}
+	}
 
-					next_board[idx] = next_cell;
-				} else {
-					// Otherwise there's no need to check for growth. Either it's disabled, or
-					// the cell is surrounded by just 1 rule.
-					let next_cell = board[idx].ruleset.next_cell_state(&scratch_board, row, col);
-					next_board[idx].state = next_cell.state;
+	/// Hands cells that have gone too long without a live cell of their own
+	/// ruleset nearby over to the background ruleset. Runs on the board that
+	/// was just generated, and changes the ruleset on both boards, since a
+	/// generation only carries states over.
+	fn decay_territory(&mut self, decay: TerritoryDecay) {
+		let (board, next_board) = match self.current_board {
+			CurrentBoard::A => (&mut self.state_a, &mut self.state_b),
+			CurrentBoard::B => (&mut self.state_b, &mut self.state_a),
+		};
+
+		for row in 0..next_board.height {
+			for col in 0..next_board.width {
+				let idx = row * next_board.width + col;
+				let ruleset = next_board[idx].ruleset;
+				if ruleset == decay.background || self.temporary_rulesets[idx].is_some() {
+					self.abandoned_for[idx] = 0;
+					continue;
+				}
+
+				let inhabited = (-1..=1).any(|dr| {
+					(-1..=1).any(|dc| {
+						next_board
+							.neighbor_idx(row, col, dr, dc)
+							.is_some_and(|idx| {
+								let cell = next_board[idx];
+								cell.ruleset == ruleset && is_live(cell)
+							})
+					})
+				});
+				if inhabited {
+					self.abandoned_for[idx] = 0;
+					continue;
+				}
+
+				self.abandoned_for[idx] = self.abandoned_for[idx].saturating_add(1);
+				if self.abandoned_for[idx] >= decay.generations {
+					next_board[idx] = self.rules.translate(next_board[idx], decay.background);
+					board[idx] = self.rules.translate(board[idx], decay.background);
+					self.abandoned_for[idx] = 0;
 				}
-				// debug_assert_eq!(next_board[idx].ruleset, board[idx].ruleset);
 			}
 		}
 	}
 
+	/// Changes the size of the board, carrying the cells over to the new
+	/// size. Temporary rulesets and states are cleared, and equilibrium
+	/// detectors start watching again from scratch, except for ones watching
+	/// a region that was cropped off, which are dropped.
+	pub fn resize(&mut self, width: usize, height: usize, fit: Fit) {
+		let from = (self.state_a.width, self.state_a.height);
+		let to = (width, height);
+		if from == to {
+			return;
+		}
+
+		let blank_cell = self.rules.off(Ruleset::default());
+		for board in [&mut self.state_a, &mut self.state_b] {
+			board.cells = fit.resample(&board.cells, from, to, blank_cell);
+			board.width = width;
+			board.height = height;
+		}
+		self.abandoned_for = fit.resample(&self.abandoned_for, from, to, 0);
+		if let Some(energy) = &mut self.energy {
+			energy.resize(width, height, fit);
+		}
+
+		self.overlaid.clear();
+		self.temporary_rulesets = vec![None; width * height];
+		self.temporary_states = vec![None; width * height];
+		self.activity.resize(width, height);
+		// A region whose seed was cropped off the board isn't there to watch
+		// any more
+		self.equilibrium_detectors.retain_mut(|detector| {
+			let scope = match detector.scope() {
+				Scope::Region(seed) => match fit.destination(seed, from, to) {
+					Some(seed) => Scope::Region(seed),
+					None => return false,
+				},
+				scope => scope,
+			};
+			*detector = EquilibriumDetector::new(scope, detector.window());
+			true
+		});
+	}
+
 	pub fn swap(&mut self) {
 		self.current_board = match self.current_board {
 			CurrentBoard::A => CurrentBoard::B,
@@ -290,51 +1132,419 @@ impl World {
 	}
 }
 
+/// Boards are only split into bands for threads to work on when every band
+/// gets at least this many rows
+const MIN_ROWS_PER_BAND: usize = 16;
+
+/// The parts of the world borrowed to compute a generation
+struct Generation<'a> {
+	board: &'a mut Board,
+	next_board: &'a mut Board,
+	growth: &'a mut Growth,
+	/// Scratch space for growth in each band of rows, when there are threads
+	band_growths: &'a mut Vec<Growth>,
+	band_contested: &'a mut Vec<Vec<usize>>,
+	workers: &'a mut Workers,
+	update_modes: &'a UpdateModes,
+	threads: usize,
+	packed_board: &'a mut PackedBoard,
+	step: Step<'a>,
+}
+
+/// What updating any cell needs, shared between threads
+#[derive(Copy, Clone)]
+struct Step<'a> {
+	rules: &'a Registry,
+	temporary_rulesets: &'a [Option<Ruleset>],
+	temporary_states: &'a [Option<u8>],
+	packed_rules: &'a [Option<LifeLike>],
+	block_rules: &'a [Option<Margolus>],
+	activity: &'a Activity,
+	/// Energy levels going into the generation, if there's an energy field
+	energy: Option<&'a [f32]>,
+	growth_enabled: bool,
+}
+
+impl Generation<'_> {
+	/// The shape of a person always updates synchronously
+	fn update_mode(&self, idx: usize) -> UpdateMode {
+		match self.step.temporary_rulesets[idx] {
+			Some(_) => UpdateMode::Synchronous,
+			None => self.update_modes.of(self.board[idx].ruleset),
+		}
+	}
+
+	/// Whether every cell updates from the board as it was at the start of
+	/// the generation, so cells can be updated in any order
+	fn is_simultaneous(&self) -> bool {
+		let simultaneous = |mode: UpdateMode| {
+			matches!(
+				mode,
+				UpdateMode::Synchronous | UpdateMode::AlphaAsynchronous { .. }
+			)
+		};
+		simultaneous(self.update_modes.global)
+			&& self
+				.update_modes
+				.rulesets
+				.iter()
+				.flatten()
+				.copied()
+				.all(simultaneous)
+	}
+
+	/// Computes the next state of a cell from the scratch board
+	fn update(&mut self, scratch_board: &mut Board, row: usize, col: usize) {
+		if self.step.growth_enabled {
+			let energy = self.step.energy(row * scratch_board.width + col);
+			self.growth
+				.find_neighboring_rulesets(self.step.rules, scratch_board, row, col, energy);
+		}
+		let idx = row * scratch_board.width + col;
+		self.step.update(
+			self.growth,
+			scratch_board,
+			row,
+			col,
+			&mut self.board[idx],
+			&mut self.next_board[idx],
+		);
+	}
+
+	/// Updates simultaneous cells in bands of rows, each on one of the workers
+	/// with its own growth scratch space, all reading the same scratch board.
+	/// Growth tries contested cells out as each competing ruleset on the
+	/// scratch board itself, so the threads only note which cells are
+	/// contested, and those are worked out one at a time afterwards. Cells
+	/// come out just as they would updating one at a time.
+	fn update_in_bands(&mut self, scratch_board: &mut Board, bands: usize) {
+		let width = scratch_board.width;
+		let rows_per_band = scratch_board.height.div_ceil(bands);
+		self.band_growths.resize_with(bands, Growth::default);
+		self.band_contested.resize_with(bands, Vec::new);
+		for growth in self.band_growths.iter_mut() {
+			growth.strategy.clone_from(&self.growth.strategy);
+			growth.claims = self.growth.claims;
+		}
+		// Room for every cell in a band to be contested, so the workers never
+		// have to grow them
+		for contested in self.band_contested.iter_mut() {
+			contested.clear();
+			contested.reserve(rows_per_band * width);
+		}
+
+		let step = self.step;
+		let update_modes = self.update_modes;
+		let shared_board = &*scratch_board;
+		let band_cells = Chunks::new(&mut self.board.cells, rows_per_band * width);
+		let band_next_cells = Chunks::new(&mut self.next_board.cells, rows_per_band * width);
+		let band_growths = Chunks::new(self.band_growths, 1);
+		let band_contested = Chunks::new(self.band_contested, 1);
+		self.workers.run(bands, &|band| {
+			// SAFETY: each band is only run once, and takes only its own chunks
+			let (cells, next_cells, growth, contested) = unsafe {
+				(
+					band_cells.get(band),
+					band_next_cells.get(band),
+					&mut band_growths.get(band)[0],
+					&mut band_contested.get(band)[0],
+				)
+			};
+			let first_idx = band * rows_per_band * width;
+			for (offset, (cell, next_cell)) in
+				cells.iter_mut().zip(next_cells.iter_mut()).enumerate()
+			{
+				let idx = first_idx + offset;
+				let (row, col) = (idx / width, idx % width);
+				if !step.activity.is_active(row, col) {
+					*next_cell = *cell;
+					continue;
+				}
+				if step.worked_out_ahead(idx, *cell) {
+					continue;
+				}
+				let mode = match step.temporary_rulesets[idx] {
+					Some(_) => UpdateMode::Synchronous,
+					None => update_modes.of(cell.ruleset),
+				};
+				if let UpdateMode::AlphaAsynchronous { alpha, seed } = mode {
+					if unit(mix(seed, shared_board.generation, row, col)) >= alpha {
+						*next_cell = *cell;
+						continue;
+					}
+				}
+
+				if step.growth_enabled {
+					growth.collect_neighboring_rulesets(step.rules, shared_board, row, col);
+					if growth.has_competing_rulesets() {
+						contested.push(idx);
+						continue;
+					}
+				}
+				step.update(growth, shared_board, row, col, cell, next_cell);
+			}
+		});
+
+		let band_contested = std::mem::take(self.band_contested);
+		for &idx in band_contested.iter().flatten() {
+			self.update(scratch_board, idx / width, idx % width);
+		}
+		*self.band_contested = band_contested;
+	}
+
+	/// Updates the cells of plain Life-like rules a word of 64 at a time.
+	/// Their neighbor counts are the same as one at a time, so the other
+	/// cells around them can't tell the difference.
+	fn update_packed(&mut self, scratch_board: &Board) {
+		if self.step.packed_rules.iter().all(Option::is_none) {
+			return;
+		}
+		self.packed_board.pack(scratch_board);
+
+		let width = scratch_board.width;
+		for row in 0..scratch_board.height {
+			for word in 0..self.packed_board.words_per_row() {
+				let alive = self.packed_board.word(row, word);
+				let counts = self.packed_board.count_neighbors(row, word);
+				// Neighboring cells mostly share a rule, so each word usually
+				// only needs working out once
+				let mut next_alive: Option<(LifeLike, u64)> = None;
+				for bit in 0..64 {
+					let Some(col) = (word * 64 + bit).checked_sub(1).filter(|&col| col < width)
+					else {
+						continue;
+					};
+					let idx = row * width + col;
+					if !self.step.activity.is_active(row, col) {
+						continue;
+					}
+					let Some(rule) = self.step.packed_rule(idx, self.board[idx]) else {
+						continue;
+					};
+					let next = match next_alive {
+						Some((last_rule, next)) if last_rule == rule => next,
+						_ => {
+							let next = rule.next_alive_packed(alive, &counts);
+							next_alive = Some((rule, next));
+							next
+						}
+					};
+					self.next_board[idx].state =
+						(next >> bit & 1) as u8 | ((alive >> bit & 1) as u8) << 1;
+				}
+			}
+		}
+	}
+
+	/// Updates the cells of block rules a whole block at a time, instead of
+	/// running the block once for each of its cells. Blocks on a torus that
+	/// wrap around are run from both sides, but each cell only once.
+	fn update_blocks(&mut self, scratch_board: &Board) {
+		if self.step.block_rules.iter().all(Option::is_none) {
+			return;
+		}
+
+		let (width, height) = (scratch_board.width, scratch_board.height);
+		let offset = (scratch_board.generation % 2) as isize;
+		for top in (-offset..height as isize).step_by(2) {
+			for left in (-offset..width as isize).step_by(2) {
+				// The cells of a block mostly share a rule, so the block usually
+				// only needs running once
+				let mut next_block: Option<(Ruleset, Option<u8>)> = None;
+				for (position, (y, x)) in [(0, 0), (0, 1), (1, 0), (1, 1)].into_iter().enumerate() {
+					let (row, col) = (top + y, left + x);
+					if row < 0 || col < 0 || row >= height as isize || col >= width as isize {
+						continue;
+					}
+					let (row, col) = (row as usize, col as usize);
+					let idx = row * width + col;
+					if !self.step.activity.is_active(row, col) {
+						continue;
+					}
+					let cell = self.board[idx];
+					let Some(rule) = self.step.block_rule(idx, cell) else {
+						continue;
+					};
+					let block = match next_block {
+						Some((ruleset, block)) if ruleset == cell.ruleset => block,
+						_ => {
+							let visibility = self.step.rules.interactions().seen_by(cell.ruleset);
+							let block = rule.next_block(scratch_board, top, left, visibility);
+							next_block = Some((cell.ruleset, block));
+							block
+						}
+					};
+					self.next_board[idx].state =
+						Margolus::cell_of(block, position, scratch_board[idx].state);
+				}
+			}
+		}
+	}
+
+	/// Carries a cell over to the next generation as it is
+	fn keep(&mut self, idx: usize) {
+		self.next_board[idx] = self.board[idx];
+	}
+
+	/// Lets the cells updated after this one see its new state. Cells held
+	/// in a temporary state keep showing it.
+	fn publish(&mut self, scratch_board: &mut Board, idx: usize) {
+		if self.step.temporary_states[idx].is_some() {
+			return;
+		}
+		scratch_board[idx] = match self.step.temporary_rulesets[idx] {
+			Some(ruleset) => Cell {
+				ruleset,
+				state: self.next_board[idx].state,
+			},
+			None => self.next_board[idx],
+		};
+	}
+}
+
+impl Step<'_> {
+	fn energy(&self, idx: usize) -> Option<f32> {
+		self.energy.map(|levels| levels[idx])
+	}
+
+	/// The Life-like rule a cell is updated with on the packed board, unless
+	/// it has to be updated by itself
+	fn packed_rule(&self, idx: usize, cell: Cell) -> Option<LifeLike> {
+		if self.temporary_rulesets[idx].is_some() || self.temporary_states[idx].is_some() {
+			return None;
+		}
+		self.packed_rules
+			.get(cell.ruleset.index())
+			.copied()
+			.flatten()
+	}
+
+	/// The block rule a cell is updated with a whole block at a time, unless
+	/// it has to be updated by itself
+	fn block_rule(&self, idx: usize, cell: Cell) -> Option<Margolus> {
+		if self.temporary_rulesets[idx].is_some() || self.temporary_states[idx].is_some() {
+			return None;
+		}
+		self.block_rules
+			.get(cell.ruleset.index())
+			.copied()
+			.flatten()
+	}
+
+	/// Whether a cell was already worked out on the packed board or with the
+	/// rest of its block
+	fn worked_out_ahead(&self, idx: usize, cell: Cell) -> bool {
+		self.packed_rule(idx, cell).is_some() || self.block_rule(idx, cell).is_some()
+	}
+
+	/// Computes the next state of a cell from the scratch board, once growth
+	/// has found the rulesets around it. Growth can hand the cell over to
+	/// another ruleset on the current board as well as the next.
+	fn update(
+		&self,
+		growth: &Growth,
+		scratch_board: &Board,
+		row: usize,
+		col: usize,
+		cell: &mut Cell,
+		next_cell: &mut Cell,
+	) {
+		let rules = self.rules;
+		let idx = row * scratch_board.width + col;
+		let energy = self.energy(idx);
+
+		if let Some(ruleset) = self.temporary_rulesets[idx] {
+			// If operating on a temporary ruleset, bypass growth. The shape of a person
+			// shouldn't grow.
+			next_cell.state = rules
+				.next_cell_state(ruleset, scratch_board, row, col, energy)
+				.state;
+		} else if self.growth_enabled && growth.has_competing_rulesets() {
+			// If growth is enabled and there's more than 1 live ruleset around a cell,
+			// compete for growth.
+			let next = growth
+				.next_live_state(scratch_board.generation, row, col)
+				.unwrap_or_else(|| {
+					rules.next_cell_state(cell.ruleset, scratch_board, row, col, energy)
+				});
+
+			if next.ruleset != cell.ruleset {
+				cell.ruleset = next.ruleset;
+			}
+
+			*next_cell = next;
+		} else {
+			// Otherwise there's no need to check for growth. Either it's disabled, or
+			// the cell is surrounded by just 1 rule.
+			next_cell.state = rules
+				.next_cell_state(cell.ruleset, scratch_board, row, col, energy)
+				.state;
+		}
+		// debug_assert_eq!(next_cell.ruleset, cell.ruleset);
+	}
+}
+
 #[derive(Copy, Clone, Debug)]
 enum CurrentBoard {
 	A,
 	B,
 }
 
-fn adjacent_live_rulesets(output: &mut Vec<Ruleset>, board: &Board, row: usize, col: usize) {
+/// Collects the rulesets of the cells around a cell, and the cell itself,
+/// that stake a claim to it.
+fn adjacent_live_rulesets(
+	output: &mut Vec<Ruleset>,
+	board: &Board,
+	row: usize,
+	col: usize,
+	is_claiming: impl Fn(Cell) -> bool,
+) {
 	output.clear();
 
-	if row > 0 {
-		adjacent_live_rulesets_row(output, board, row - 1, col);
-	}
-
-	adjacent_live_rulesets_row(output, board, row, col);
-
-	if row < board.height - 1 {
-		adjacent_live_rulesets_row(output, board, row + 1, col);
+	if board.is_edge(row, col) {
+		for dr in -1..=1 {
+			for dc in -1..=1 {
+				if let Some(idx) = board.neighbor_idx(row, col, dr, dc) {
+					push_ruleset_if_claiming(output, board, idx, &is_claiming);
+				}
+			}
+		}
+	} else {
+		adjacent_live_rulesets_row(output, board, row - 1, col, &is_claiming);
+		adjacent_live_rulesets_row(output, board, row, col, &is_claiming);
+		adjacent_live_rulesets_row(output, board, row + 1, col, &is_claiming);
 	}
 
 	output.sort_unstable();
 }
 
 #[inline]
-fn push_ruleset_if_live(output: &mut Vec<Ruleset>, board: &Board, idx: usize) {
-	// Consider pushing ruleset if cell's state is nonzero, not only if cell's
-	// smallest bit is on. This will make the growth algorithm consider the
-	// rulesets of cells which aren't alive but have history in its upper bits,
-	// like "refractory" in brian's brian, or "was alive" in anti-life.
-	if board[idx].state & 0b01 > 0 {
+fn push_ruleset_if_claiming(
+	output: &mut Vec<Ruleset>,
+	board: &Board,
+	idx: usize,
+	is_claiming: &impl Fn(Cell) -> bool,
+) {
+	if is_claiming(board[idx]) {
 		output.push(board[idx].ruleset);
 	}
 }
 
-fn adjacent_live_rulesets_row(output: &mut Vec<Ruleset>, board: &Board, row: usize, col: usize) {
+fn adjacent_live_rulesets_row(
+	output: &mut Vec<Ruleset>,
+	board: &Board,
+	row: usize,
+	col: usize,
+	is_claiming: &impl Fn(Cell) -> bool,
+) {
 	let idx = row * board.width + col;
+	push_ruleset_if_claiming(output, board, idx - 1, is_claiming);
+	push_ruleset_if_claiming(output, board, idx, is_claiming);
+	push_ruleset_if_claiming(output, board, idx + 1, is_claiming);
+}
 
-	if col > 0 {
-		push_ruleset_if_live(output, board, idx - 1);
-	}
-
-	push_ruleset_if_live(output, board, idx);
-
-	if col < board.width - 1 {
-		push_ruleset_if_live(output, board, idx + 1);
-	}
+fn is_live(cell: Cell) -> bool {
+	cell.state & 0b01 > 0
 }
 
 fn sort_rulesets_by_population(result: &mut Vec<(Ruleset, u8)>, rulesets: &[Ruleset]) {
@@ -346,25 +1556,63 @@ fn sort_rulesets_by_population(result: &mut Vec<(Ruleset, u8)>, rulesets: &[Rule
 			result.push((ruleset, 1));
 		}
 	}
-	result.sort_unstable_by(|(_, count_a), (_, count_b)| count_b.cmp(count_a));
+	result.sort_unstable_by(|(ruleset_a, count_a), (ruleset_b, count_b)| {
+		count_b.cmp(count_a).then(ruleset_a.cmp(ruleset_b))
+	});
 }
 
 #[cfg(test)]
 mod test {
-	use super::{adjacent_live_rulesets, sort_rulesets_by_population, Board, Cell, Ruleset};
+	use super::{
+		adjacent_live_rulesets, is_live, mix, sort_rulesets_by_population, Board, Boundary, Cell,
+		Fit, Growth, GrowthStrategy, Ruleset, TerritoryClaims, TerritoryDecay, UpdateMode, World,
+	};
+	use crate::colors::{fill_cell_colors, Color};
+	use crate::energy::{Energy, Supply};
+	use crate::equilibrium::Scope;
+	use crate::noise::{Effect, Noise, Pattern};
+	use crate::rules::Registry;
+	use crate::stroke::Stroke;
+	use std::alloc::{GlobalAlloc, Layout, System};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	/// Counts the allocations made on every thread, so tests can check that
+	/// steady state frames don't make any
+	struct CountingAllocator;
+
+	static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+	unsafe impl GlobalAlloc for CountingAllocator {
+		unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+			ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+			System.alloc(layout)
+		}
+
+		unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+			System.dealloc(ptr, layout)
+		}
+
+		unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+			ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+			System.realloc(ptr, layout, new_size)
+		}
+	}
+
+	#[global_allocator]
+	static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 	#[test]
 	fn adjacent_live_rulesets_clusters_rulesets() {
 		let cells = [
-			Ruleset::Life,
-			Ruleset::Life,
-			Ruleset::LatticeGas,
-			Ruleset::BriansBrain,
-			Ruleset::Diamoeba,
-			Ruleset::Seeds,
-			Ruleset::AntiLife,
-			Ruleset::AntiLife,
-			Ruleset::Seeds,
+			Ruleset::LIFE,
+			Ruleset::LIFE,
+			Ruleset::LATTICE_GAS,
+			Ruleset::BRIANS_BRAIN,
+			Ruleset::DIAMOEBA,
+			Ruleset::SEEDS,
+			Ruleset::ANTI_LIFE,
+			Ruleset::ANTI_LIFE,
+			Ruleset::SEEDS,
 		]
 		.into_iter()
 		.map(|ruleset| Cell {
@@ -376,37 +1624,39 @@ mod test {
 			cells,
 			width: 3,
 			height: 3,
+			generation: 0,
+			boundary: Boundary::Dead,
 		};
 
 		let expected = vec![
-			Ruleset::Life,
-			Ruleset::Life,
-			Ruleset::AntiLife,
-			Ruleset::AntiLife,
-			Ruleset::BriansBrain,
-			Ruleset::Seeds,
-			Ruleset::Seeds,
-			Ruleset::Diamoeba,
-			Ruleset::LatticeGas,
+			Ruleset::LIFE,
+			Ruleset::LIFE,
+			Ruleset::ANTI_LIFE,
+			Ruleset::ANTI_LIFE,
+			Ruleset::BRIANS_BRAIN,
+			Ruleset::SEEDS,
+			Ruleset::SEEDS,
+			Ruleset::DIAMOEBA,
+			Ruleset::LATTICE_GAS,
 		];
 
 		let mut result = Vec::with_capacity(9);
-		adjacent_live_rulesets(&mut result, &board, 1, 1);
+		adjacent_live_rulesets(&mut result, &board, 1, 1, is_live);
 		assert_eq!(result, expected);
 	}
 
 	#[test]
 	fn adjacent_live_rulesets_ignores_dead_cells() {
 		let cells = [
-			(Ruleset::Life, 0b01),
-			(Ruleset::Life, 0b00),
-			(Ruleset::LatticeGas, 0b01),
-			(Ruleset::BriansBrain, 0b11),
-			(Ruleset::Diamoeba, 0b10),
-			(Ruleset::Seeds, 0b01),
-			(Ruleset::AntiLife, 0b10),
-			(Ruleset::AntiLife, 0b00),
-			(Ruleset::Seeds, 0b01),
+			(Ruleset::LIFE, 0b01),
+			(Ruleset::LIFE, 0b00),
+			(Ruleset::LATTICE_GAS, 0b01),
+			(Ruleset::BRIANS_BRAIN, 0b11),
+			(Ruleset::DIAMOEBA, 0b10),
+			(Ruleset::SEEDS, 0b01),
+			(Ruleset::ANTI_LIFE, 0b10),
+			(Ruleset::ANTI_LIFE, 0b00),
+			(Ruleset::SEEDS, 0b01),
 		]
 		.into_iter()
 		.map(|(ruleset, state)| Cell { ruleset, state })
@@ -416,42 +1666,520 @@ mod test {
 			cells,
 			width: 3,
 			height: 3,
+			generation: 0,
+			boundary: Boundary::Dead,
 		};
 
 		let expected = vec![
-			Ruleset::Life,
-			Ruleset::BriansBrain,
-			Ruleset::Seeds,
-			Ruleset::Seeds,
-			Ruleset::LatticeGas,
+			Ruleset::LIFE,
+			Ruleset::BRIANS_BRAIN,
+			Ruleset::SEEDS,
+			Ruleset::SEEDS,
+			Ruleset::LATTICE_GAS,
 		];
 
 		let mut result = Vec::with_capacity(9);
-		adjacent_live_rulesets(&mut result, &board, 1, 1);
+		adjacent_live_rulesets(&mut result, &board, 1, 1, is_live);
 		assert_eq!(result, expected);
 	}
 
+	#[test]
+	fn neighbors_follow_the_boundary() {
+		let mut board = Board {
+			cells: (0..9)
+				.map(|state| Cell {
+					state,
+					..Default::default()
+				})
+				.collect(),
+			width: 3,
+			height: 3,
+			generation: 0,
+			boundary: Boundary::Dead,
+		};
+		assert_eq!(board.neighbor_idx(0, 0, -1, -1), None);
+		assert_eq!(board.neighbor_state(0, 0, -1, -1), 0b00);
+
+		board.boundary = Boundary::Alive;
+		assert_eq!(board.neighbor_idx(0, 0, -1, -1), None);
+		assert_eq!(board.neighbor_state(0, 0, -1, -1), 0b01);
+
+		board.boundary = Boundary::Torus;
+		assert_eq!(board.neighbor_idx(0, 0, -1, -1), Some(8));
+		assert_eq!(board.neighbor_idx(2, 2, 1, 0), Some(2));
+
+		board.boundary = Boundary::Mirror;
+		assert_eq!(board.neighbor_idx(0, 0, -1, -1), Some(0));
+		assert_eq!(board.neighbor_idx(2, 1, 2, 0), Some(4));
+		assert_eq!(board.wrapping_neighbor_idx(0, 0, -1, -1), None);
+	}
+
+	#[test]
+	fn the_default_boundary_keeps_rules_going_at_the_edges() {
+		// A particle about to run into the wall bounces back instead of
+		// leaving the board
+		let mut world = World::new(4, 4);
+		for idx in 0..16 {
+			world.set_ruleset(idx, Ruleset::LATTICE_GAS);
+		}
+		world.board_mut()[5].state = 0b00101;
+		world.generate(false);
+		world.swap();
+		assert_eq!(world.board()[4].state, 0b00011);
+		world.generate(false);
+		world.swap();
+		assert_eq!(world.board()[5].state, 0b00011);
+
+		// Anti-Life's background carries on past the edge, so it stays put
+		let mut world = World::new(5, 5);
+		for idx in 0..25 {
+			world.set_ruleset(idx, Ruleset::ANTI_LIFE);
+			world.board_mut()[idx].state = 0b01;
+		}
+		world.generate(false);
+		world.swap();
+		assert!(world.board().iter().all(|cell| cell.state & 0b01 == 0b01));
+	}
+
 	#[test]
 	fn sort_rulesets_by_population_sorts_em() {
 		let neighboring_rulesets = vec![
-			Ruleset::Life,
-			Ruleset::Life,
-			Ruleset::LatticeGas,
-			Ruleset::BriansBrain,
-			Ruleset::Diamoeba,
-			Ruleset::AntiLife,
-			Ruleset::AntiLife,
-			Ruleset::AntiLife,
-			Ruleset::Seeds,
+			Ruleset::LIFE,
+			Ruleset::LIFE,
+			Ruleset::LATTICE_GAS,
+			Ruleset::BRIANS_BRAIN,
+			Ruleset::DIAMOEBA,
+			Ruleset::ANTI_LIFE,
+			Ruleset::ANTI_LIFE,
+			Ruleset::ANTI_LIFE,
+			Ruleset::SEEDS,
 		];
 
 		let mut result: Vec<(Ruleset, u8)> = Vec::with_capacity(9);
 
 		sort_rulesets_by_population(&mut result, &neighboring_rulesets);
 
-		// Because of an unstable sort, all the rulesets with count=1 will
-		// have an undefined order, and also we don't care what it is
-		assert_eq!(result[0], (Ruleset::AntiLife, 3));
-		assert_eq!(result[1], (Ruleset::Life, 2));
+		assert_eq!(result[0], (Ruleset::ANTI_LIFE, 3));
+		assert_eq!(result[1], (Ruleset::LIFE, 2));
+		// Ties are ordered by ruleset
+		assert_eq!(result[2], (Ruleset::BRIANS_BRAIN, 1));
+		assert_eq!(result[5], (Ruleset::LATTICE_GAS, 1));
+	}
+
+	#[test]
+	fn present_cells_claim_territory() {
+		// Refractory Brian's Brain cells aren't alive, but they are present
+		#[rustfmt::skip]
+		let cells = [
+			(Ruleset::LIFE, 0b01), (Ruleset::BRIANS_BRAIN, 0b10), (Ruleset::BRIANS_BRAIN, 0b10),
+			(Ruleset::LIFE, 0b00), (Ruleset::LIFE, 0b00), (Ruleset::LIFE, 0b00),
+			(Ruleset::LIFE, 0b00), (Ruleset::LIFE, 0b00), (Ruleset::LIFE, 0b00),
+		]
+		.into_iter()
+		.map(|(ruleset, state)| Cell { ruleset, state })
+		.collect::<Vec<_>>();
+		let mut board = Board {
+			cells,
+			width: 3,
+			height: 3,
+			generation: 0,
+			boundary: Boundary::Dead,
+		};
+
+		let rules = Registry::default();
+		let mut growth = Growth::default();
+		growth.find_neighboring_rulesets(&rules, &mut board, 1, 1, None);
+		assert!(!growth.has_competing_rulesets());
+
+		growth.claims = TerritoryClaims::Present;
+		growth.find_neighboring_rulesets(&rules, &mut board, 1, 1, None);
+		assert!(growth.has_competing_rulesets());
+		assert_eq!(growth.population(Ruleset::BRIANS_BRAIN), 2);
+	}
+
+	#[test]
+	fn growth_strategies_pick_a_winner() {
+		// Two Life cells and a Diamoeba cell surround a dead cell. Both rules
+		// would give birth to it.
+		#[rustfmt::skip]
+		let cells = [
+			(Ruleset::LIFE, 0b01), (Ruleset::LIFE, 0b01), (Ruleset::DIAMOEBA, 0b01),
+			(Ruleset::LIFE, 0b00), (Ruleset::LIFE, 0b00), (Ruleset::LIFE, 0b00),
+			(Ruleset::LIFE, 0b00), (Ruleset::LIFE, 0b00), (Ruleset::LIFE, 0b00),
+		]
+		.into_iter()
+		.map(|(ruleset, state)| Cell { ruleset, state })
+		.collect::<Vec<_>>();
+		let board = Board {
+			cells,
+			width: 3,
+			height: 3,
+			generation: 0,
+			boundary: Boundary::Dead,
+		};
+
+		let rules = Registry::default();
+		let winner = |strategy| {
+			let mut growth = Growth {
+				strategy,
+				..Default::default()
+			};
+			growth.find_neighboring_rulesets(&rules, &mut board.clone(), 1, 1, None);
+			growth.next_live_state(0, 1, 1).map(|cell| cell.ruleset)
+		};
+
+		assert_eq!(winner(GrowthStrategy::Plurality), Some(Ruleset::LIFE));
+		assert_eq!(
+			winner(GrowthStrategy::Dominance(vec![Ruleset::DIAMOEBA])),
+			Some(Ruleset::DIAMOEBA)
+		);
+		assert_eq!(
+			winner(GrowthStrategy::RockPaperScissors(vec![
+				Ruleset::DIAMOEBA,
+				Ruleset::LIFE,
+				Ruleset::SEEDS,
+			])),
+			Some(Ruleset::DIAMOEBA)
+		);
+		assert_eq!(
+			winner(GrowthStrategy::RockPaperScissors(vec![
+				Ruleset::LIFE,
+				Ruleset::DIAMOEBA,
+				Ruleset::SEEDS,
+			])),
+			Some(Ruleset::LIFE)
+		);
+
+		let mut strengths = vec![1.0; Ruleset::DIAMOEBA.index() + 1];
+		strengths[Ruleset::DIAMOEBA.index()] = 3.0;
+		assert_eq!(
+			winner(GrowthStrategy::Strength(strengths.clone())),
+			Some(Ruleset::DIAMOEBA)
+		);
+		// An equal score falls back to population
+		strengths[Ruleset::DIAMOEBA.index()] = 2.0;
+		assert_eq!(
+			winner(GrowthStrategy::Strength(strengths)),
+			Some(Ruleset::LIFE)
+		);
+
+		let seeded = GrowthStrategy::WeightedRandom { seed: 7 };
+		assert_eq!(winner(seeded.clone()), winner(seeded));
+	}
+
+	#[test]
+	fn abandoned_territory_decays_to_the_background() {
+		let mut world = World::new(8, 8);
+		world.fill_ruleset(Ruleset::SEEDS);
+		for idx in 0..32 {
+			world.set_ruleset(idx, Ruleset::LIFE);
+		}
+		// A block, which lives forever in the top left
+		for idx in [9, 10, 17, 18] {
+			world.board_mut()[idx].state = 0b01;
+		}
+		world.set_territory_decay(Some(TerritoryDecay {
+			background: Ruleset::SEEDS,
+			generations: 3,
+		}));
+
+		for _ in 0..2 {
+			world.generate(false);
+			world.swap();
+		}
+		assert_eq!(world.board()[6].ruleset, Ruleset::LIFE);
+
+		world.generate(false);
+		world.swap();
+		let board = world.board();
+		// Cells next to the block are still inhabited, and the rest decayed
+		assert_eq!(board[0].ruleset, Ruleset::LIFE);
+		assert_eq!(board[27].ruleset, Ruleset::LIFE);
+		assert_eq!(board[6].ruleset, Ruleset::SEEDS);
+		assert_eq!(board[28].ruleset, Ruleset::SEEDS);
+
+		// Both boards agree on the new ruleset
+		world.generate(false);
+		world.swap();
+		assert_eq!(world.board()[6].ruleset, Ruleset::SEEDS);
+		assert_eq!(world.board()[18].state & 0b01, 0b01);
+	}
+
+	#[test]
+	fn committed_silhouettes_are_translated() {
+		let mut world = World::new(4, 4);
+		world.fill_ruleset(Ruleset::LATTICE_GAS);
+		let (board, next_board) = world.this_board_and_next();
+		board[5].state = 0b10101;
+		next_board[5].state = 0b10101;
+		let gas = board[5];
+		world.set_temporary_ruleset(5, Ruleset::LIFE);
+		world.set_temporary_ruleset(6, Ruleset::LIFE);
+		world.set_temporary_state(6, 0b01);
+		world.commit_temporary_rulesets();
+		world.clear_temporary_rulesets();
+		world.clear_temporary_states();
+
+		// The particles become something Life can read, on both boards
+		let translated = world.rules().translate(gas, Ruleset::LIFE);
+		assert_eq!(world.board()[5], translated);
+		assert_eq!(world.board()[5].state & !0b11, 0);
+		let (_, next_board) = world.this_board_and_next();
+		assert_eq!(next_board[5], translated);
+		assert_eq!(next_board[6].ruleset, Ruleset::LIFE);
+		assert_eq!(next_board[6].state, 0b01);
+	}
+
+	#[test]
+	fn update_modes_change_what_cells_see() {
+		let live = |world: &World| {
+			world
+				.board()
+				.iter()
+				.enumerate()
+				.filter(|(_, cell)| is_live(**cell))
+				.map(|(idx, _)| idx)
+				.collect::<Vec<_>>()
+		};
+
+		// Cells that never get their chance to update stay as they are,
+		// unless their ruleset has its own mode
+		let mut world = World::new(6, 6);
+		for idx in [7, 13, 19] {
+			world.board_mut()[idx].state = 0b01;
+		}
+		world.set_update_mode(UpdateMode::AlphaAsynchronous {
+			alpha: 0.0,
+			seed: 1,
+		});
+		world.generate(false);
+		world.swap();
+		assert_eq!(live(&world), vec![7, 13, 19]);
+
+		world.set_ruleset_update_mode(Ruleset::LIFE, Some(UpdateMode::Synchronous));
+		world.generate(false);
+		world.swap();
+		assert_eq!(live(&world), vec![12, 13, 14]);
+
+		// Two seeds next to each other. Synchronously they seed the four cells
+		// above and below them, but on a checkerboard the white squares see
+		// the seeds the black squares just planted, and seed two more.
+		let mut world = World::new(6, 6);
+		world.fill_ruleset(Ruleset::SEEDS);
+		for idx in [7, 8] {
+			world.board_mut()[idx].state = 0b01;
+		}
+		world.set_update_mode(UpdateMode::Checkerboard);
+		world.generate(false);
+		world.swap();
+		assert_eq!(live(&world), vec![1, 2, 3, 13, 14, 15]);
+	}
+
+	#[test]
+	fn threads_generate_the_same_boards_as_one() {
+		let world = |threads| {
+			let mut world = World::new(64, 64);
+			world.set_threads(threads);
+			let rulesets = [
+				Ruleset::LIFE,
+				Ruleset::SEEDS,
+				Ruleset::BRIANS_BRAIN,
+				Ruleset::ANNEAL,
+			];
+			for idx in 0..64 * 64 {
+				let (row, col) = (idx / 64, idx % 64);
+				world.set_ruleset(idx, rulesets[(row / 16 + col / 16) % 4]);
+				world.board_mut()[idx].state = (mix(1, 0, row, col) % 2) as u8;
+			}
+			world.set_growth_strategy(GrowthStrategy::WeightedRandom { seed: 3 });
+			world.set_ruleset_update_mode(
+				Ruleset::ANNEAL,
+				Some(UpdateMode::AlphaAsynchronous {
+					alpha: 0.5,
+					seed: 5,
+				}),
+			);
+			world
+		};
+
+		let mut serial = world(1);
+		let mut parallel = world(4);
+		for _ in 0..20 {
+			serial.generate(true);
+			serial.swap();
+			parallel.generate(true);
+			parallel.swap();
+			assert_eq!(serial.board().cells, parallel.board().cells);
+		}
+	}
+
+	#[test]
+	fn steady_frames_allocate_nothing() {
+		// Allocations are counted across the whole process, worker threads
+		// included, so the frames run in a process of their own where no
+		// other tests are allocating
+		if std::env::var_os("COUNT_ALLOCATIONS").is_none() {
+			let (_, module) = module_path!().split_once("::").unwrap();
+			let output = std::process::Command::new(std::env::current_exe().unwrap())
+				.args(["--exact", "--test-threads=1"])
+				.arg(format!("{}::steady_frames_allocate_nothing", module))
+				.env("COUNT_ALLOCATIONS", "1")
+				.output()
+				.unwrap();
+			let stdout = String::from_utf8_lossy(&output.stdout);
+			assert!(
+				output.status.success() && stdout.contains("1 passed"),
+				"{}{}",
+				stdout,
+				String::from_utf8_lossy(&output.stderr)
+			);
+			return;
+		}
+
+		// Generating with the default threads, and with bands of rows on
+		// workers even when there's only one core
+		let default_threads = World::new(1, 1).threads();
+		for threads in [default_threads, 4] {
+			let mut world = World::new(64, 64);
+			world.set_threads(threads);
+			for idx in 0..64 * 64 {
+				let (row, col) = (idx / 64, idx % 64);
+				if col >= 32 {
+					world.set_ruleset(idx, Ruleset::BRIANS_BRAIN);
+				}
+				world.board_mut()[idx].state = (mix(8, 0, row, col) % 2) as u8;
+			}
+			world.set_energy(Some(Energy::new(64, 64, Supply::Uniform(1.0))));
+			world.set_noise(Some(Noise::new(Effect::Flip, Pattern::Uniform, 0.01)));
+			world.watch_equilibrium(Scope::Board, 16);
+			for idx in 20 * 64..20 * 64 + 8 {
+				world.set_temporary_ruleset(idx, Ruleset::SEEDS);
+			}
+
+			// A frame paints, generates and fills in the colors to draw, with
+			// and without growth and the overlays, and with cells updating
+			// in bands and one at a time
+			let mut stroke = Stroke::new(64 * 64);
+			let mut colors = Vec::new();
+			let frame = |world: &mut World, stroke: &mut Stroke, colors: &mut Vec<Color>| {
+				for (growth_enabled, overlays, mode) in [
+					(false, false, None),
+					(true, true, None),
+					(false, true, Some(UpdateMode::Checkerboard)),
+				] {
+					world.set_ruleset_update_mode(Ruleset::BRIANS_BRAIN, mode);
+					if stroke.touch(30 * 64 + 30) {
+						world.board_mut()[30 * 64 + 30].state = 0b01;
+					}
+					stroke.end();
+					world.generate(growth_enabled);
+					world.swap();
+					fill_cell_colors(colors, world, overlays, overlays, overlays);
+				}
+			};
+			frame(&mut world, &mut stroke, &mut colors);
+			let before = ALLOCATIONS.load(Ordering::SeqCst);
+			for _ in 0..10 {
+				frame(&mut world, &mut stroke, &mut colors);
+			}
+			assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), before);
+		}
+	}
+
+	#[test]
+	fn resizing_crops_or_scales_the_board() {
+		let mut world = World::new(4, 4);
+		world.board_mut()[5].state = 0b01;
+		world.set_ruleset(15, Ruleset::SEEDS);
+		world.watch_equilibrium(Scope::Region(15), 8);
+
+		world.resize(8, 2, Fit::Crop);
+		let board = world.board();
+		assert_eq!((board.width, board.height, board.cells.len()), (8, 2, 16));
+		assert_eq!(board[9].state, 0b01);
+		assert!(board.iter().filter(|cell| cell.state != 0).count() == 1);
+		assert!(world.equilibrium_detectors().is_empty());
+
+		let mut world = World::new(4, 4);
+		world.board_mut()[5].state = 0b01;
+		world.set_ruleset(15, Ruleset::SEEDS);
+		world.watch_equilibrium(Scope::Region(15), 8);
+		world.resize(8, 8, Fit::Scale);
+		let board = world.board();
+		for idx in [18, 19, 26, 27] {
+			assert_eq!(board[idx].state, 0b01);
+		}
+		assert_eq!(board[63].ruleset, Ruleset::SEEDS);
+		assert_eq!(world.equilibrium_detectors()[0].scope(), Scope::Region(54));
+		assert_eq!(world.temporary_states().len(), 64);
+
+		world.generate(false);
+		world.swap();
+	}
+
+	#[test]
+	fn packed_cells_generate_like_single_cells() {
+		let mut world = World::new(80, 24);
+		world.set_boundary(Boundary::Torus);
+		let rulesets = [Ruleset::LIFE, Ruleset::SEEDS, Ruleset::BRIANS_BRAIN];
+		for idx in 0..80 * 24 {
+			let (row, col) = (idx / 80, idx % 80);
+			world.set_ruleset(idx, rulesets[(row / 8 + col / 20) % 3]);
+			world.board_mut()[idx].state = (mix(4, 0, row, col) % 2) as u8;
+		}
+
+		for _ in 0..10 {
+			let board = world.board().clone();
+			let expected = (0..board.cells.len())
+				.map(|idx| {
+					world.rules().next_cell_state(
+						board[idx].ruleset,
+						&board,
+						idx / 80,
+						idx % 80,
+						None,
+					)
+				})
+				.collect::<Vec<_>>();
+			world.generate(false);
+			world.swap();
+			assert_eq!(world.board().cells, expected);
+		}
+	}
+
+	#[test]
+	fn blocks_generate_like_single_cells() {
+		for boundary in [Boundary::Torus, Boundary::Dead] {
+			let mut world = World::new(30, 20);
+			world.set_boundary(boundary);
+			// Borders between the rulesets cut through blocks
+			let rulesets = [Ruleset::CRITTERS, Ruleset::BILLIARD_BALL, Ruleset::LIFE];
+			let mut interactions = world.rules().interactions().clone();
+			interactions.set(Ruleset::CRITTERS, Ruleset::LIFE, 0);
+			world.rules_mut().set_interactions(interactions);
+			for idx in 0..30 * 20 {
+				let (row, col) = (idx / 30, idx % 30);
+				world.set_ruleset(idx, rulesets[(row / 7 + col / 9) % 3]);
+				world.board_mut()[idx].state = (mix(8, 0, row, col) % 2) as u8;
+			}
+
+			for _ in 0..10 {
+				let board = world.board().clone();
+				let expected = (0..board.cells.len())
+					.map(|idx| {
+						world.rules().next_cell_state(
+							board[idx].ruleset,
+							&board,
+							idx / 30,
+							idx % 30,
+							None,
+						)
+					})
+					.collect::<Vec<_>>();
+				world.generate(false);
+				world.swap();
+				assert_eq!(world.board().cells, expected);
+			}
+		}
 	}
 }